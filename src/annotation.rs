@@ -1,5 +1,5 @@
 use nom::{
-    bytes::complete::{is_not, tag, take_until, take_while},
+    bytes::complete::{tag, take_while, take_while1},
     character::complete::{char, multispace0, space0},
     combinator::{map, opt},
     multi::many0,
@@ -12,31 +12,144 @@ use std::collections::HashMap;
 pub enum AnnotationArg {
     String(String),
     Class(String),
+    /// A dotted identifier other than a class literal, such as the enum constant
+    /// `RequestMethod.GET`. Constants of classes, such as `Constants.BASE_PATH`, look the same.
+    Enum(String),
+    /// A constant without a qualifying type, such as a statically imported `BASE_PATH`.
+    Reference(String),
+    Boolean(bool),
+    Number(i64),
     Array(Vec<AnnotationArg>),
+    /// A nested annotation, such as `@ComponentScan.Filter(...)`.
+    Annotation(Box<Annotation>),
+}
+
+impl AnnotationArg {
+    pub fn as_string(&self) -> Option<&str> {
+        match self {
+            AnnotationArg::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_class(&self) -> Option<&str> {
+        match self {
+            AnnotationArg::Class(class) => Some(class),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<i64> {
+        match self {
+            AnnotationArg::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[AnnotationArg]> {
+        match self {
+            AnnotationArg::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_annotation(&self) -> Option<&Annotation> {
+        match self {
+            AnnotationArg::Annotation(annotation) => Some(annotation),
+            _ => None,
+        }
+    }
+}
+
+/// Takes the input up to the `close` delimiter matching an already consumed `open`,
+/// skipping over nested pairs and string literals.
+pub(crate) fn take_balanced(open: char, close: char) -> impl Fn(&str) -> IResult<&str, &str> {
+    move |input: &str| {
+        let mut depth = 0;
+        let mut in_string = false;
+        let mut chars = input.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if c == '"' {
+                in_string = !in_string;
+            } else if in_string {
+                // An escaped quote does not end the string
+                if c == '\\' {
+                    chars.next();
+                }
+                continue;
+            } else if c == open {
+                depth += 1;
+            } else if c == close {
+                if depth == 0 {
+                    return Ok((&input[i..], &input[..i]));
+                }
+                depth -= 1;
+            }
+        }
+        Err(nom::Err::Error(nom::error::make_error(
+            input,
+            nom::error::ErrorKind::TakeUntil,
+        )))
+    }
+}
+
+/// Takes the contents of a string literal up to its closing quote, as written in the source,
+/// so with escapes such as `\"` left in place.
+fn string_contents(input: &str) -> IResult<&str, &str> {
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return Ok((&input[i..], &input[..i])),
+            _ => {}
+        }
+    }
+    Err(nom::Err::Error(nom::error::make_error(
+        input,
+        nom::error::ErrorKind::TakeUntil,
+    )))
 }
 
 pub fn parse_arg(input: &str) -> IResult<&str, AnnotationArg> {
     let is_string = input.starts_with('"');
     let is_array = input.starts_with('{');
+    let is_annotation = input.starts_with('@');
     let res = if is_string {
-        let (input, between) = delimited(char('"'), is_not("\""), char('"'))(input)?;
+        let (input, between) = delimited(char('"'), string_contents, char('"'))(input)?;
         (input, AnnotationArg::String(between.to_string()))
+    } else if is_annotation {
+        let (input, annotation) = parse_annotation(input)?;
+        (input, AnnotationArg::Annotation(Box::new(annotation)))
     } else if is_array {
-        let (input, between) = delimited(char('{'), is_not("}"), char('}'))(input)?;
-        let between = between.trim_start();
+        let (input, between) = delimited(char('{'), take_balanced('{', '}'), char('}'))(input)?;
+        let between = between.trim();
         let (_, values) = nom::multi::separated_list0(
             |input| {
+                let (input, _) = multispace0(input)?;
                 let (input, _) = tag(",")(input)?;
-                let (input, _) = space0(input)?;
+                let (input, _) = multispace0(input)?;
                 Ok((input, ()))
             },
             parse_arg,
         )(between)?;
         (input, AnnotationArg::Array(values))
     } else {
-        let (input, name) = take_until(".class")(input)?;
-        let (input, _) = tag(".class")(input)?;
-        (input, AnnotationArg::Class(name.to_string()))
+        let (input, token) = take_while1(|c: char| {
+            c.is_alphanumeric() || c == '.' || c == '_' || c == '$' || c == '-'
+        })(input)?;
+        if let Some(class) = token.strip_suffix(".class") {
+            (input, AnnotationArg::Class(class.to_string()))
+        } else if let Ok(boolean) = token.parse() {
+            (input, AnnotationArg::Boolean(boolean))
+        } else if let Ok(number) = token.trim_end_matches(['L', 'l']).parse() {
+            (input, AnnotationArg::Number(number))
+        } else if token.contains('.') {
+            (input, AnnotationArg::Enum(token.to_string()))
+        } else {
+            (input, AnnotationArg::Reference(token.to_string()))
+        }
     };
     Ok(res)
 }
@@ -47,10 +160,11 @@ pub fn parse_key_value_pairs(input: &str) -> IResult<&str, HashMap<String, Annot
         let (input, key) = take_while(|c: char| c.is_alphanumeric())(input)?;
         let (input, _) = space0(input)?;
         let (input, _) = tag("=")(input)?;
-        let (input, _) = space0(input)?;
+        let (input, _) = multispace0(input)?;
         let (input, value) = parse_arg(input)?;
+        let (input, _) = multispace0(input)?;
         let (input, _) = opt(tag(","))(input)?;
-        let (input, _) = space0(input)?;
+        let (input, _) = multispace0(input)?;
         Ok((input, (key.to_string(), value)))
     })(input)?;
     let values = key_value_pairs.into_iter().collect();
@@ -63,8 +177,19 @@ pub enum AnnotationArgs {
     Multi(HashMap<String, AnnotationArg>),
 }
 
+/// Whether the arguments start with a `key = value` pair rather than a single value.
+fn is_key_value(input: &str) -> bool {
+    let key_end = input
+        .find(|c: char| !c.is_alphanumeric())
+        .unwrap_or(input.len());
+    key_end > 0 && input[key_end..].trim_start().starts_with('=')
+}
+
 pub fn parse_args(input: &str) -> IResult<&str, AnnotationArgs> {
-    if input.contains('=') {
+    let input = input.trim();
+    if input.is_empty() {
+        Ok((input, AnnotationArgs::Multi(HashMap::new())))
+    } else if is_key_value(input) {
         map(parse_key_value_pairs, AnnotationArgs::Multi)(input)
     } else {
         map(parse_arg, AnnotationArgs::Single)(input)
@@ -85,6 +210,14 @@ impl Annotation {
         }
     }
 
+    /// Looks up a keyed argument, such as `name` in `@Foo(name = "bar")`.
+    pub fn get(&self, key: &str) -> Option<&AnnotationArg> {
+        match &self.args {
+            AnnotationArgs::Single(_) => None,
+            AnnotationArgs::Multi(vs) => vs.get(key),
+        }
+    }
+
     pub fn name(&self) -> &str {
         self.name.as_ref()
     }
@@ -92,17 +225,37 @@ impl Annotation {
     pub fn args(&self) -> &AnnotationArgs {
         &self.args
     }
+
+    /// The precedence given by an `@Order` or `@Priority` annotation, lower values first.
+    pub fn order(&self) -> Option<i64> {
+        if !matches!(self.name(), "Order" | "Priority") {
+            return None;
+        }
+        match self.value() {
+            // Without a value, @Order means the lowest precedence
+            None => Some(i32::MAX.into()),
+            Some(AnnotationArg::Enum(constant) | AnnotationArg::Reference(constant)) => {
+                match constant.rsplit('.').next().unwrap_or(constant) {
+                    "HIGHEST_PRECEDENCE" => Some(i32::MIN.into()),
+                    "LOWEST_PRECEDENCE" => Some(i32::MAX.into()),
+                    _ => None,
+                }
+            }
+            Some(arg) => arg.as_number(),
+        }
+    }
 }
 
 pub fn parse_annotation(input: &str) -> IResult<&str, Annotation> {
     let (input, _) = tag("@")(input)?;
-    let (input, name) = take_while(|c: char| c.is_alphanumeric())(input)?;
+    let (input, name) =
+        take_while(|c: char| c.is_alphanumeric() || c == '_' || c == '$' || c == '.')(input)?;
     let (input, _) = multispace0(input)?;
 
     // Parse args if there are any
     let (input, args) = if input.starts_with('(') {
         // Parse args between parentheses
-        let (input, between) = delimited(char('('), is_not(")"), char(')'))(input)?;
+        let (input, between) = delimited(char('('), take_balanced('(', ')'), char(')'))(input)?;
         let (_, args) = parse_args(between)?;
         let (input, _) = multispace0(input)?;
         (input, args)
@@ -133,8 +286,80 @@ pub fn parse_annotations(mut input: &str) -> IResult<&str, Vec<Annotation>> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use crate::annotation::{parse_annotation, Annotation, AnnotationArg, AnnotationArgs};
 
+    #[test]
+    pub fn annotation_arg_as_string() {
+        assert_eq!(
+            Some("a"),
+            AnnotationArg::String("a".to_string()).as_string()
+        );
+        assert_eq!(None, AnnotationArg::Class("A".to_string()).as_string());
+    }
+
+    #[test]
+    pub fn annotation_arg_as_class() {
+        assert_eq!(Some("A"), AnnotationArg::Class("A".to_string()).as_class());
+        assert_eq!(None, AnnotationArg::String("a".to_string()).as_class());
+    }
+
+    #[test]
+    pub fn parse_annotation_with_escaped_quotes_succeeds() {
+        let (input, annotation) =
+            parse_annotation(r#"@Value(value = "say \"hi)\"", name = "x") class"#).unwrap();
+        assert_eq!("class", input);
+        assert_eq!(
+            Some(&AnnotationArg::String(r#"say \"hi)\""#.to_string())),
+            annotation.value()
+        );
+        assert_eq!(
+            Some(&AnnotationArg::String("x".to_string())),
+            annotation.get("name")
+        );
+    }
+
+    #[test]
+    pub fn annotation_order() {
+        let order = |input| parse_annotation(input).unwrap().1.order();
+        assert_eq!(Some(5), order("@Order(5)"));
+        assert_eq!(Some(-1), order("@Priority(-1)"));
+        assert_eq!(
+            Some(i32::MIN.into()),
+            order("@Order(Ordered.HIGHEST_PRECEDENCE)")
+        );
+        assert_eq!(Some(i32::MAX.into()), order("@Order"));
+        assert_eq!(None, order("@Service"));
+    }
+
+    #[test]
+    pub fn annotation_arg_as_array() {
+        let values = vec![AnnotationArg::Class("A".to_string())];
+        assert_eq!(
+            Some(values.as_slice()),
+            AnnotationArg::Array(values.clone()).as_array()
+        );
+        assert_eq!(None, AnnotationArg::Class("A".to_string()).as_array());
+    }
+
+    #[test]
+    pub fn annotation_get() {
+        let (_, annotation) = parse_annotation("@Foo(name = \"bar\", value = Baz.class)").unwrap();
+        assert_eq!(
+            Some(&AnnotationArg::String("bar".to_string())),
+            annotation.get("name")
+        );
+        assert_eq!(
+            Some(&AnnotationArg::Class("Baz".to_string())),
+            annotation.get("value")
+        );
+        assert_eq!(None, annotation.get("url"));
+
+        let (_, annotation) = parse_annotation("@Foo(\"bar\")").unwrap();
+        assert_eq!(None, annotation.get("name"));
+    }
+
     #[test]
     pub fn parse_annotation_with_class_value_succeeds() {
         assert_eq!(
@@ -180,6 +405,174 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn parse_annotation_with_enum_value_succeeds() {
+        assert_eq!(
+            Ok((
+                "",
+                Annotation {
+                    name: "RequestMapping".to_string(),
+                    args: AnnotationArgs::Multi(
+                        vec![(
+                            "method".to_string(),
+                            AnnotationArg::Enum("RequestMethod.GET".to_string())
+                        )]
+                        .into_iter()
+                        .collect()
+                    )
+                }
+            )),
+            parse_annotation("@RequestMapping(method = RequestMethod.GET)")
+        );
+    }
+
+    #[test]
+    pub fn parse_annotation_with_any_enum_value_succeeds() {
+        assert_eq!(
+            Ok((
+                "",
+                Annotation {
+                    name: "Foo".to_string(),
+                    args: AnnotationArgs::Multi(
+                        vec![(
+                            "mode".to_string(),
+                            AnnotationArg::Enum("MyEnum.X".to_string())
+                        )]
+                        .into_iter()
+                        .collect()
+                    )
+                }
+            )),
+            parse_annotation("@Foo(mode = MyEnum.X)")
+        );
+    }
+
+    #[test]
+    pub fn parse_annotation_with_constant_reference_succeeds() {
+        assert_eq!(
+            Ok((
+                "",
+                Annotation {
+                    name: "Foo".to_string(),
+                    args: AnnotationArgs::Single(AnnotationArg::Reference("X".to_string()))
+                }
+            )),
+            parse_annotation("@Foo(X)")
+        );
+    }
+
+    #[test]
+    pub fn parse_annotation_with_literal_values_succeeds() {
+        assert_eq!(
+            Ok((
+                "",
+                Annotation {
+                    name: "Foo".to_string(),
+                    args: AnnotationArgs::Multi(
+                        vec![
+                            ("a".to_string(), AnnotationArg::Boolean(false)),
+                            ("b".to_string(), AnnotationArg::Number(-5)),
+                            ("c".to_string(), AnnotationArg::Reference("X".to_string()))
+                        ]
+                        .into_iter()
+                        .collect()
+                    )
+                }
+            )),
+            parse_annotation("@Foo(a = false, b = -5, c = X)")
+        );
+    }
+
+    #[test]
+    pub fn parse_annotation_with_newline_before_parenthesis_succeeds() {
+        assert_eq!(
+            Ok((
+                "class",
+                Annotation {
+                    name: "ComponentScan".to_string(),
+                    args: AnnotationArgs::Single(AnnotationArg::String("a.b.c".to_string()))
+                }
+            )),
+            parse_annotation("@ComponentScan\n(\"a.b.c\")\nclass")
+        );
+    }
+
+    #[test]
+    pub fn parse_annotation_with_empty_parentheses_succeeds() {
+        let expected = Annotation {
+            name: "Foo".to_string(),
+            args: AnnotationArgs::Multi(HashMap::new()),
+        };
+        assert_eq!(
+            Ok(("class", expected.clone())),
+            parse_annotation("@Foo() class")
+        );
+        assert_eq!(Ok(("class", expected)), parse_annotation("@Foo ( ) class"));
+    }
+
+    #[test]
+    pub fn parse_annotation_with_underscore_and_digits_succeeds() {
+        let (input, annotation) = parse_annotation("@My_Annotation$2 class").unwrap();
+        assert_eq!("My_Annotation$2", annotation.name());
+        assert_eq!("class", input);
+        let (_, annotation) = parse_annotation("@V2Controller").unwrap();
+        assert_eq!("V2Controller", annotation.name());
+    }
+
+    #[test]
+    pub fn parse_annotation_with_nested_annotation_succeeds() {
+        let (input, annotation) = parse_annotation(
+            "@ComponentScan(\n    basePackages = \"a.b\",\n    excludeFilters = @ComponentScan.Filter(type = FilterType.REGEX, pattern = \"a\\\\.b\\\\..*(Test)\")\n) class",
+        )
+        .unwrap();
+        assert_eq!("class", input);
+        let filter = annotation
+            .get("excludeFilters")
+            .and_then(AnnotationArg::as_annotation)
+            .unwrap();
+        assert_eq!("ComponentScan.Filter", filter.name());
+        assert_eq!(
+            Some(&AnnotationArg::Enum("FilterType.REGEX".to_string())),
+            filter.get("type")
+        );
+        assert_eq!(
+            Some(&AnnotationArg::String("a\\\\.b\\\\..*(Test)".to_string())),
+            filter.get("pattern")
+        );
+    }
+
+    #[test]
+    pub fn parse_annotation_with_array_of_annotations_succeeds() {
+        let (input, annotation) = parse_annotation(
+            "@ComponentScan(includeFilters = {\n    @Filter(type = FilterType.ANNOTATION, classes = Foo.class),\n    @Filter(type = X, classes = {A.class, B.class})\n}) class",
+        )
+        .unwrap();
+        assert_eq!("class", input);
+        let filters: Vec<_> = annotation
+            .get("includeFilters")
+            .and_then(AnnotationArg::as_array)
+            .unwrap()
+            .iter()
+            .map(|f| f.as_annotation().unwrap())
+            .collect();
+        assert_eq!(2, filters.len());
+        assert_eq!(
+            Some(&AnnotationArg::Class("Foo".to_string())),
+            filters[0].get("classes")
+        );
+        assert_eq!(
+            Some(&AnnotationArg::Reference("X".to_string())),
+            filters[1].get("type")
+        );
+        assert_eq!(
+            Some(&AnnotationArg::Array(vec![
+                AnnotationArg::Class("A".to_string()),
+                AnnotationArg::Class("B".to_string())
+            ])),
+            filters[1].get("classes")
+        );
+    }
+
     #[test]
     pub fn parse_annotation_with_key_value_pairs_succeeds() {
         assert_eq!(
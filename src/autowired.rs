@@ -1,13 +1,71 @@
+/// How an injection point picks one of several beans of its type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Qualifier {
+    /// A bean name, as in `@Qualifier("main")`.
+    Name(String),
+    /// A custom qualifier, an annotation meta-annotated with `@Qualifier`, without the `@`.
+    /// It picks the bean carrying the same annotation.
+    Annotation(String),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Autowired {
     name: String,
     class: String,
+    required: bool,
+    collection: bool,
+    qualifier: Option<Qualifier>,
+    /// Names of the other annotations on the field, without the `@`.
+    annotations: Vec<String>,
+    /// The 1-based line of the injection annotation, when parsed from source.
+    line: Option<usize>,
 }
 
 impl Autowired {
     pub fn new(class: String, name: String) -> Self {
-        Autowired { class, name }
+        Autowired {
+            class,
+            name,
+            required: true,
+            collection: false,
+            qualifier: None,
+            annotations: Vec::new(),
+            line: None,
+        }
+    }
+
+    /// Sets whether the dependency is required, as with `@Autowired(required = false)`.
+    pub fn with_required(self, required: bool) -> Self {
+        Autowired { required, ..self }
+    }
+
+    /// Marks the dependency as injected through a collection such as `List<Foo>`,
+    /// which binds to every bean of the element type.
+    pub fn with_collection(self, collection: bool) -> Self {
+        Autowired { collection, ..self }
+    }
+
+    /// Sets the qualifier picking the bean, such as the name given by `@Qualifier`.
+    pub fn with_qualifier(self, qualifier: Option<Qualifier>) -> Self {
+        Autowired { qualifier, ..self }
+    }
+
+    /// Sets the names of the other annotations on the field, such as custom qualifiers.
+    pub fn with_annotations(self, annotations: Vec<String>) -> Self {
+        Autowired {
+            annotations,
+            ..self
+        }
+    }
+
+    /// Sets the line the field is declared on.
+    pub fn with_line(self, line: usize) -> Self {
+        Autowired {
+            line: Some(line),
+            ..self
+        }
     }
+
     pub fn name(&self) -> &str {
         self.name.as_ref()
     }
@@ -15,4 +73,24 @@ impl Autowired {
     pub fn class(&self) -> &str {
         self.class.as_ref()
     }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    pub fn collection(&self) -> bool {
+        self.collection
+    }
+
+    pub fn qualifier(&self) -> Option<&Qualifier> {
+        self.qualifier.as_ref()
+    }
+
+    pub fn annotations(&self) -> &[String] {
+        self.annotations.as_ref()
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
 }
@@ -1,19 +1,88 @@
 use std::str::FromStr;
 
 use nom::{
-    bytes::complete::{tag, take_while},
-    character::complete::{multispace0, space0},
+    branch::alt,
+    bytes::complete::{is_not, tag, take_while},
+    character::complete::{char, multispace0, multispace1, space0},
     combinator::opt,
+    error::ErrorKind,
+    multi::{many0, many1},
+    sequence::{delimited, pair, preceded, terminated},
     IResult,
 };
 
-use super::annotation::{parse_annotation, AnnotationArg};
+use super::{
+    annotation::{parse_annotation, take_balanced, AnnotationArg},
+    autowired::Qualifier,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Parameter {
     pub annotations: Vec<String>,
     pub class: String,
     pub name: String,
+    /// Whether every bean of the type is injected, as into `List<Foo>` or `Foo...`.
+    pub collection: bool,
+    /// The qualifier picking the bean, such as the name given by `@Qualifier`.
+    pub qualifier: Option<Qualifier>,
+}
+
+/// Splits a parameter on whitespace, keeping annotations together with their
+/// parenthesized arguments and generic types together with their type arguments.
+fn tokenize_parameter(s: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut start = None;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' | '<' if !in_string => depth += 1,
+            ')' | '>' if !in_string => depth -= 1,
+            c if c.is_whitespace() && !in_string && depth == 0 => {
+                // An annotation may be separated from its arguments by whitespace
+                let continues = s[i..].trim_start().starts_with('(')
+                    && start.is_some_and(|start: usize| s[start..i].starts_with('@'));
+                if let (Some(token_start), false) = (start, continues) {
+                    tokens.push(&s[token_start..i]);
+                    start = None;
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(start) = start {
+        tokens.push(&s[start..]);
+    }
+    tokens
+}
+
+impl Parameter {
+    /// A parameter with the given annotations, such as `@Qualifier("x")`, which qualifies it.
+    pub fn new(annotations: Vec<String>, class: String, name: String) -> Self {
+        let qualifier = annotations
+            .iter()
+            .filter_map(|a| parse_annotation(a).ok())
+            .find(|(_, a)| a.name() == "Qualifier")
+            .and_then(|(_, a)| {
+                a.value()
+                    .and_then(AnnotationArg::as_string)
+                    .map(|name| Qualifier::Name(name.to_string()))
+            });
+        Parameter {
+            annotations,
+            class,
+            name,
+            collection: false,
+            qualifier,
+        }
+    }
+
+    pub fn qualifier(&self) -> Option<&Qualifier> {
+        self.qualifier.as_ref()
+    }
 }
 
 impl FromStr for Parameter {
@@ -21,7 +90,11 @@ impl FromStr for Parameter {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let s = s.trim();
-        let mut parts = s.split_whitespace().collect::<Vec<_>>();
+        // `final` only concerns the method body
+        let mut parts: Vec<_> = tokenize_parameter(s)
+            .into_iter()
+            .filter(|t| *t != "final")
+            .collect();
         if parts.len() < 2 {
             return Err(format!("missing parameter class or name {}", s));
         }
@@ -30,11 +103,16 @@ impl FromStr for Parameter {
         if !parts.iter().all(|a| a.starts_with('@')) {
             return Err(format!("missing @ on annotation {}", s));
         }
-        Ok(Parameter {
-            annotations: parts.into_iter().map(|a| a.to_string()).collect(),
-            class: class.to_string(),
-            name: name.to_string(),
-        })
+        // Varargs are passed as an array
+        let class = match class.strip_suffix("...") {
+            Some(element) => format!("{}[]", element),
+            None => class.to_string(),
+        };
+        Ok(Parameter::new(
+            parts.into_iter().map(|a| a.to_string()).collect(),
+            class,
+            name.to_string(),
+        ))
     }
 }
 
@@ -43,6 +121,15 @@ pub struct Bean {
     name: String,
     class: String,
     parameters: Vec<Parameter>,
+    from_factory: bool,
+    init_method: Option<String>,
+    destroy_method: Option<String>,
+    primary: bool,
+    order: Option<i64>,
+    /// The concrete type returned by the method body, see [`infer_implementation`].
+    implementation: Option<String>,
+    /// The 1-based line of the `@Bean` method's first annotation, when parsed from source.
+    line: Option<usize>,
 }
 
 impl Bean {
@@ -51,8 +138,42 @@ impl Bean {
             class,
             name,
             parameters,
+            from_factory: false,
+            init_method: None,
+            destroy_method: None,
+            primary: false,
+            order: None,
+            implementation: None,
+            line: None,
+        }
+    }
+
+    /// Replaces the parameters of the method.
+    pub fn with_parameters(self, parameters: Vec<Parameter>) -> Self {
+        Bean { parameters, ..self }
+    }
+
+    /// Sets the concrete type returned by the method body.
+    pub fn with_implementation(self, implementation: Option<String>) -> Self {
+        Bean {
+            implementation,
+            ..self
+        }
+    }
+
+    /// Marks the bean as `@Primary`.
+    pub fn with_primary(self, primary: bool) -> Self {
+        Bean { primary, ..self }
+    }
+
+    /// Sets the line the bean method is declared on.
+    pub fn with_line(self, line: usize) -> Self {
+        Bean {
+            line: Some(line),
+            ..self
         }
     }
+
     pub fn name(&self) -> &str {
         self.name.as_ref()
     }
@@ -64,62 +185,335 @@ impl Bean {
     pub fn parameters(&self) -> &[Parameter] {
         self.parameters.as_ref()
     }
+
+    /// Whether the bean is produced by a `FactoryBean` returned from the bean method.
+    pub fn from_factory(&self) -> bool {
+        self.from_factory
+    }
+
+    /// The method named by `initMethod` on the `@Bean` annotation.
+    pub fn init_method(&self) -> Option<&str> {
+        self.init_method.as_deref()
+    }
+
+    /// The method named by `destroyMethod` on the `@Bean` annotation.
+    pub fn destroy_method(&self) -> Option<&str> {
+        self.destroy_method.as_deref()
+    }
+
+    /// Whether the bean is annotated with `@Primary`.
+    pub fn primary(&self) -> bool {
+        self.primary
+    }
+
+    /// The precedence given by `@Order` or `@Priority`, lower values first.
+    pub fn order(&self) -> Option<i64> {
+        self.order
+    }
+
+    /// The concrete type returned by the method body, when it differs from the return type.
+    pub fn implementation(&self) -> Option<&str> {
+        self.implementation.as_deref()
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+}
+
+/// Parses a type name along with its generic arguments, such as `FactoryBean<Foo>`.
+pub fn parse_type(input: &str) -> IResult<&str, &str> {
+    let (rest, _) =
+        take_while(|c: char| c.is_alphanumeric() || c == '_' || c == '$' || c == '.')(input)?;
+    if !rest.starts_with('<') {
+        return Ok((rest, &input[..input.len() - rest.len()]));
+    }
+    let mut depth = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    let end = input.len() - rest.len() + i + 1;
+                    return Ok((&input[end..], &input[..end]));
+                }
+            }
+            _ => {}
+        }
+    }
+    Err(nom::Err::Error(nom::error::make_error(
+        input,
+        nom::error::ErrorKind::TakeUntil,
+    )))
+}
+
+/// Takes the contents of a parameter list up to its closing parenthesis,
+/// skipping over parentheses that belong to annotation arguments.
+pub fn take_parameter_list(input: &str) -> IResult<&str, &str> {
+    let mut depth = 0;
+    let mut in_string = false;
+    for (i, c) in input.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string && depth == 0 => return Ok((&input[i..], &input[..i])),
+            ')' if !in_string => depth -= 1,
+            _ => {}
+        }
+    }
+    Ok(("", input))
+}
+
+/// Splits a parameter list on the commas that separate parameters,
+/// ignoring commas inside annotation arguments.
+pub fn split_parameters(params: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut start = 0;
+    for (i, c) in params.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' | '<' if !in_string => depth += 1,
+            ')' | '>' if !in_string => depth -= 1,
+            ',' if !in_string && depth == 0 => {
+                parts.push(&params[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&params[start..]);
+    parts
+}
+
+/// Parses the parameters of a method or constructor declaration, the text between its
+/// parentheses, skipping any that cannot be parsed.
+pub fn parse_parameter_list(params: &str) -> Vec<Parameter> {
+    let params = params.trim();
+    if params.is_empty() {
+        return Vec::new();
+    }
+    split_parameters(params)
+        .into_iter()
+        .filter_map(|p| Parameter::from_str(p).ok())
+        .collect()
+}
+
+/// Takes a method body from its opening brace up to the matching closing brace,
+/// skipping over braces in string and character literals. A method without a body,
+/// or whose body is not closed, gives an empty body.
+fn take_body(input: &str) -> &str {
+    if !input.starts_with('{') {
+        return "";
+    }
+    let mut depth = 0;
+    let mut quote = None;
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return &input[..=i];
+                }
+            }
+            _ => {}
+        }
+    }
+    ""
+}
+
+/// Methods starting a builder chain, as in `WebClient.builder()...build()`.
+const BUILDER_METHODS: &[&str] = &["builder", "newBuilder", "create"];
+
+/// Infers the concrete type a bean method body returns, from `return new Foo(...)` or a
+/// builder chain such as `return Foo.builder()...build()`. A builder type named `FooBuilder`,
+/// as in `FooBuilder.create().build()`, is taken to produce `Foo`.
+///
+/// This is a heuristic: only the first `return` is looked at, and local variables,
+/// factory methods and the type returned by `build()` itself are not followed.
+pub fn infer_implementation(body: &str) -> Option<String> {
+    let pos = body
+        .match_indices("return")
+        .map(|(pos, _)| pos)
+        .find(|&pos| {
+            let before = body[..pos].chars().next_back();
+            let after = body[pos + "return".len()..].chars().next();
+            !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+                && after.is_some_and(char::is_whitespace)
+        })?;
+    let statement = body[pos + "return".len()..].split(';').next()?.trim();
+    if let Some(constructed) = statement.strip_prefix("new") {
+        let (_, class) = parse_type(constructed.trim_start()).ok()?;
+        let class = class.split('<').next().unwrap_or(class);
+        return Some(class.to_string()).filter(|c| !c.is_empty());
+    }
+    let (receiver, _) = statement.split_once('(')?;
+    let (class, method) = receiver.rsplit_once('.')?;
+    let builds = statement
+        .strip_suffix(')')
+        .and_then(|s| s.trim_end().strip_suffix('('))
+        .is_some_and(|s| s.trim_end().ends_with(".build"));
+    if !BUILDER_METHODS.contains(&method.trim()) || !builds {
+        return None;
+    }
+    let class = class.trim();
+    let class = match class.strip_suffix("Builder") {
+        Some(built) if !built.is_empty() && !built.ends_with('.') => built,
+        _ => class,
+    };
+    Some(class.to_string())
 }
 
+/// Parses a `@Bean` method, without inferring its implementation from the body.
 pub fn parse_bean(input: &str) -> IResult<&str, Bean> {
-    let (input, annotation) = parse_annotation(input)?;
-    // Skip visibility modifier
-    let (input, _) = opt(tag("public"))(input)?;
-    let (input, _) = opt(tag("protected"))(input)?;
-    let (input, _) = opt(tag("private"))(input)?;
+    parse_bean_with(false)(input)
+}
+
+/// Parses a `@Bean` method, inferring the implementation from its body when asked to.
+/// The type of a Groovy `def` method is always inferred, since it has no other.
+pub fn parse_bean_with(infer_implementations: bool) -> impl Fn(&str) -> IResult<&str, Bean> {
+    move |input| parse_bean_inner(input, infer_implementations)
+}
+
+fn parse_bean_inner(input: &str, infer_implementations: bool) -> IResult<&str, Bean> {
+    // The @Bean annotation may be mixed with others, such as @Primary or @Profile
+    let (input, annotations) = many1(parse_annotation)(input)?;
+    let annotation = annotations
+        .iter()
+        .find(|a| a.name() == "Bean")
+        .ok_or_else(|| nom::Err::Error(nom::error::make_error(input, ErrorKind::Tag)))?;
+    let primary = annotations.iter().any(|a| a.name() == "Primary");
+    let order = annotations.iter().find_map(|a| a.order());
+    // Skip modifiers, including `default` on bean methods of interfaces
+    let (input, _) = many0(terminated(
+        alt((
+            tag("public"),
+            tag("protected"),
+            tag("private"),
+            tag("static"),
+            tag("final"),
+            tag("default"),
+        )),
+        multispace1,
+    ))(input)?;
     let (input, _) = space0(input)?;
-    // Get return type
-    let (input, class) = take_while(|c: char| c.is_alphanumeric())(input)?;
+    // Skip the type parameters of a generic method, as in `<T> Foo<T> foo()`
+    let (input, type_params) = opt(terminated(
+        delimited(char('<'), take_balanced('<', '>'), char('>')),
+        multispace0,
+    ))(input)?;
+    let type_params: Vec<&str> = type_params
+        .map(split_parameters)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|p| p.split_whitespace().next())
+        .collect();
+    // Skip type annotations such as `@NonNull`, which may come before or after the return type
+    let (input, _) = many0(parse_annotation)(input)?;
+    // Get return type, looking through factory beans to the type they produce
+    let (input, return_type) = parse_type(input)?;
+    let (class, from_factory) = match return_type.strip_prefix("FactoryBean<") {
+        Some(inner) => (inner.strip_suffix('>').unwrap_or(inner).trim(), true),
+        None => (return_type, false),
+    };
+    // A type parameter as the return type, as in `<T> T foo()`, tells nothing about the bean
+    if type_params.contains(&class) {
+        return Err(nom::Err::Error(nom::error::make_error(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+    // An array of beans is drawn as its element type, like collections are
+    let (input, _) = many0(preceded(multispace0, tag("[]")))(input)?;
     let (input, _) = multispace0(input)?;
+    let (input, _) = many0(parse_annotation)(input)?;
     // Get method name
     let (input, name) = take_while(|c: char| c.is_alphanumeric())(input)?;
     // Get parameters
     let (input, _) = tag("(")(input)?;
     let (input, _) = multispace0(input)?;
-    let (input, params) = take_while(|c: char| c != ')')(input)?;
-    let params = params.trim_end_matches(')').trim();
-    let params: Vec<Parameter> = if !params.is_empty() {
-        params
-            .trim_end_matches(')')
-            .split(',')
-            .filter_map(|p| Parameter::from_str(p).ok())
-            .collect()
-    } else {
-        vec![]
-    };
+    let (input, params) = take_parameter_list(input)?;
+    let (input, _) = opt(char(')'))(input)?;
+    let (input, _) = multispace0(input)?;
+    // Skip the checked exceptions the method declares
+    let (input, _) = opt(pair(tag("throws"), is_not("{;")))(input)?;
+    let params = parse_parameter_list(params);
     // See if name has been overridden
-    let overriden_name = match annotation.value() {
+    let overriden_name = match annotation.value().or_else(|| annotation.get("name")) {
         Some(AnnotationArg::String(name)) => Some(name.clone()),
         _ => None,
     };
+    // Only record an implementation that tells more than the return type. A factory
+    // bean constructs the factory rather than the bean it produces.
+    // Groovy methods declared with `def` have no return type, so the body has to tell it
+    let untyped = class == "def";
+    let implementation = if infer_implementations || untyped {
+        infer_implementation(take_body(input))
+            .filter(|i| !from_factory && i.rsplit('.').next() != class.rsplit('.').next())
+    } else {
+        None
+    };
+    let (class, implementation) = match implementation {
+        Some(implementation) if untyped => (implementation, None),
+        implementation => (class.to_string(), implementation),
+    };
+    let callback = |key| {
+        annotation
+            .get(key)
+            .and_then(AnnotationArg::as_string)
+            .map(String::from)
+    };
     Ok((
         input,
         Bean {
             name: overriden_name.unwrap_or_else(|| name.to_string()),
-            class: class.to_string(),
+            class,
             parameters: params,
+            from_factory,
+            init_method: callback("initMethod"),
+            destroy_method: callback("destroyMethod"),
+            primary,
+            order,
+            implementation,
+            line: None,
         },
     ))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::bean::{parse_bean, Bean, Parameter};
+    use crate::autowired::Qualifier;
+    use crate::bean::{
+        infer_implementation, parse_bean, parse_bean_with, parse_parameter_list, take_body, Bean,
+        Parameter,
+    };
 
     #[test]
     pub fn parse_bean_succeeds() {
         assert_eq!(
             Ok((
-                ") { ... }",
+                "{ ... }",
                 Bean {
                     name: "myBean".to_string(),
                     class: "MyBean".to_string(),
-                    parameters: vec![]
+                    parameters: vec![],
+                    from_factory: false,
+                    init_method: None,
+                    destroy_method: None,
+                    primary: false,
+                    order: None,
+                    implementation: None,
+                    line: None,
                 }
             )),
             parse_bean("@Bean\n    private MyBean myBean( ) { ... }")
@@ -130,49 +524,394 @@ mod tests {
     pub fn parse_bean_succeeds2() {
         assert_eq!(
             Ok((
-                ") { ... }",
+                "{ ... }",
                 Bean {
                     name: "myBean".to_string(),
                     class: "MyBean".to_string(),
                     parameters: vec![Parameter {
                         annotations: vec![],
                         class: "FooBean".to_string(),
-                        name: "fooBean".to_string()
-                    }]
+                        name: "fooBean".to_string(),
+                        collection: false,
+                        qualifier: None,
+                    }],
+                    from_factory: false,
+                    init_method: None,
+                    destroy_method: None,
+                    primary: false,
+                    order: None,
+                    implementation: None,
+                    line: None,
                 }
             )),
             parse_bean("@Bean\n    private MyBean myBean(FooBean fooBean) { ... }")
         );
     }
 
+    #[test]
+    pub fn parse_bean_with_generic_parameter_succeeds() {
+        let (_, bean) =
+            parse_bean("@Bean\n    public Foo foo(Map<String, Bar> bars, Baz baz) { ... }")
+                .unwrap();
+        let classes: Vec<_> = bean.parameters().iter().map(|p| p.class.as_str()).collect();
+        assert_eq!(vec!["Map<String, Bar>", "Baz"], classes);
+    }
+
     #[test]
     pub fn parse_bean_with_name_succeeds() {
         assert_eq!(
             Ok((
-                ") { ... }",
+                "{ ... }",
                 Bean {
                     name: "newName".to_string(),
                     class: "MyBean".to_string(),
                     parameters: vec![Parameter {
                         annotations: vec![],
                         class: "FooBean".to_string(),
-                        name: "fooBean".to_string()
-                    }]
+                        name: "fooBean".to_string(),
+                        collection: false,
+                        qualifier: None,
+                    }],
+                    from_factory: false,
+                    init_method: None,
+                    destroy_method: None,
+                    primary: false,
+                    order: None,
+                    implementation: None,
+                    line: None,
                 }
             )),
             parse_bean("@Bean(\"newName\")\n    private MyBean myBean(FooBean fooBean) { ... }")
         );
     }
 
+    #[test]
+    pub fn parse_bean_with_multiline_parameters_succeeds() {
+        assert_eq!(
+            Ok((
+                "",
+                Bean {
+                    name: "foo".to_string(),
+                    class: "Foo".to_string(),
+                    parameters: vec![
+                        Parameter {
+                            annotations: vec!["@Qualifier(\"x\")".to_string()],
+                            class: "Bar".to_string(),
+                            name: "bar".to_string(),
+                            collection: false,
+                            qualifier: Some(Qualifier::Name("x".to_string())),
+                        },
+                        Parameter {
+                            annotations: vec![],
+                            class: "Baz".to_string(),
+                            name: "baz".to_string(),
+                            collection: false,
+                            qualifier: None,
+                        }
+                    ],
+                    from_factory: false,
+                    init_method: None,
+                    destroy_method: None,
+                    primary: false,
+                    order: None,
+                    implementation: None,
+                    line: None,
+                }
+            )),
+            parse_bean("@Bean\npublic Foo foo(\n  @Qualifier(\"x\") Bar bar,\n  Baz baz\n)")
+        );
+    }
+
+    #[test]
+    pub fn parse_factory_bean_succeeds() {
+        assert_eq!(
+            Ok((
+                "{ ... }",
+                Bean {
+                    name: "fooFactory".to_string(),
+                    class: "Foo".to_string(),
+                    parameters: vec![],
+                    from_factory: true,
+                    init_method: None,
+                    destroy_method: None,
+                    primary: false,
+                    order: None,
+                    implementation: None,
+                    line: None,
+                }
+            )),
+            parse_bean("@Bean\n    public FactoryBean<Foo> fooFactory() { ... }")
+        );
+    }
+
+    #[test]
+    pub fn parse_bean_with_lifecycle_methods_succeeds() {
+        let (_, bean) = parse_bean(
+            "@Bean(initMethod = \"start\", destroyMethod = \"stop\")\n    public Foo foo() { ... }",
+        )
+        .unwrap();
+        assert_eq!("foo", bean.name());
+        assert_eq!(Some("start"), bean.init_method());
+        assert_eq!(Some("stop"), bean.destroy_method());
+    }
+
+    #[test]
+    pub fn parse_bean_with_throws_succeeds() {
+        let (input, bean) = parse_bean(
+            "@Bean\n    public DataSource ds() throws SQLException, IOException { ... }",
+        )
+        .unwrap();
+        assert_eq!("{ ... }", input);
+        assert_eq!("ds", bean.name());
+        assert_eq!("DataSource", bean.class());
+    }
+
+    #[test]
+    pub fn parse_bean_after_other_annotations_succeeds() {
+        let (_, bean) = parse_bean(
+            "@Primary\n    @Bean(\"main\")\n    @Profile(\"prod\")\n    public Foo foo() { ... }",
+        )
+        .unwrap();
+        assert_eq!("main", bean.name());
+        assert_eq!("Foo", bean.class());
+        assert!(bean.primary());
+
+        assert!(parse_bean("@Primary\n    public Foo foo() { ... }").is_err());
+    }
+
+    #[test]
+    pub fn parse_bean_with_type_annotation_succeeds() {
+        let (input, bean) =
+            parse_bean("@Bean\n    public @NonNull MyBean myBean() { ... }").unwrap();
+        assert_eq!("{ ... }", input);
+        assert_eq!("MyBean", bean.class());
+        assert_eq!("myBean", bean.name());
+
+        let (_, bean) = parse_bean("@Bean\n    public MyBean @NonNull myBean() { ... }").unwrap();
+        assert_eq!("MyBean", bean.class());
+        assert_eq!("myBean", bean.name());
+    }
+
+    #[test]
+    pub fn parse_default_bean_succeeds() {
+        let (input, bean) = parse_bean("@Bean\n    default MyBean myBean() { ... }").unwrap();
+        assert_eq!("{ ... }", input);
+        assert_eq!("MyBean", bean.class());
+        assert_eq!("myBean", bean.name());
+
+        let (_, bean) = parse_bean("@Bean\n    public static Foo foo() { ... }").unwrap();
+        assert_eq!("Foo", bean.class());
+    }
+
+    #[test]
+    pub fn parse_array_bean_succeeds() {
+        let (input, bean) = parse_bean("@Bean\n    public Foo[] foos() { ... }").unwrap();
+        assert_eq!("{ ... }", input);
+        assert_eq!("Foo", bean.class());
+        assert_eq!("foos", bean.name());
+
+        let (_, bean) = parse_bean("@Bean\n    public Foo [][] foos() { ... }").unwrap();
+        assert_eq!("Foo", bean.class());
+        assert_eq!("foos", bean.name());
+    }
+
+    #[test]
+    pub fn parse_generic_method_bean_succeeds() {
+        let (input, bean) = parse_bean("@Bean\n    public <T> Foo<T> foo() { ... }").unwrap();
+        assert_eq!("{ ... }", input);
+        assert_eq!("Foo<T>", bean.class());
+        assert_eq!("foo", bean.name());
+
+        let (_, bean) = parse_bean(
+            "@Bean\n    public static <K, V extends Comparable<V>>\n    Cache<K, V> cache(CacheManager manager) { ... }",
+        )
+        .unwrap();
+        assert_eq!("Cache<K, V>", bean.class());
+        assert_eq!("cache", bean.name());
+        assert_eq!(1, bean.parameters().len());
+
+        assert!(parse_bean("@Bean\n    public <T> T anything() { ... }").is_err());
+    }
+
+    #[test]
+    pub fn parse_ordered_bean_succeeds() {
+        let (_, bean) = parse_bean("@Bean\n    @Order(5)\n    public Foo foo() { ... }").unwrap();
+        assert_eq!(Some(5), bean.order());
+    }
+
+    #[test]
+    pub fn parse_builder_bean_succeeds() {
+        let (input, bean) = parse_bean_with(true)(
+            r#"@Bean
+    public ObjectMapper objectMapper() {
+        return JsonMapper.builder()
+            .addModule(new JavaTimeModule())
+            .build();
+    }"#,
+        )
+        .unwrap();
+        assert!(input.starts_with('{'));
+        assert_eq!("ObjectMapper", bean.class());
+        assert_eq!(Some("JsonMapper"), bean.implementation());
+
+        let (_, bean) = parse_bean_with(true)(
+            "@Bean
+    DataSource ds() { return new HikariDataSource(config()); }",
+        )
+        .unwrap();
+        assert_eq!(Some("HikariDataSource"), bean.implementation());
+
+        // The builder produces the return type itself
+        let (_, bean) = parse_bean_with(true)(
+            "@Bean
+    DataSource ds() { return DataSourceBuilder.create().url(\"{x}\").build(); }",
+        )
+        .unwrap();
+        assert_eq!(None, bean.implementation());
+
+        // Only inferred when asked to
+        let (_, bean) = parse_bean(
+            "@Bean
+    DataSource ds() { return new HikariDataSource(config()); }",
+        )
+        .unwrap();
+        assert_eq!(None, bean.implementation());
+    }
+
+    #[test]
+    pub fn take_body_skips_literals() {
+        assert_eq!("{ return '}'; }", take_body("{ return '}'; } rest"));
+        assert_eq!(
+            "{ s = \"\\\"}\" + '\"'; }",
+            take_body("{ s = \"\\\"}\" + '\"'; } rest")
+        );
+        assert_eq!("", take_body("; @Bean Foo foo() { return new Foo(); }"));
+        assert_eq!("", take_body("{ return new Foo();"));
+    }
+
+    #[test]
+    pub fn infer_implementation_from_body() {
+        let infer = infer_implementation;
+        assert_eq!(Some("Foo".to_string()), infer("{ return new Foo(); }"));
+        assert_eq!(
+            Some("ArrayList".to_string()),
+            infer("{ return new ArrayList<>(); }")
+        );
+        assert_eq!(
+            Some("DataSource".to_string()),
+            infer("{ return DataSourceBuilder.create().url(url).build(); }")
+        );
+        assert_eq!(
+            Some("WebClient".to_string()),
+            infer("{ return WebClient.builder()\n    .baseUrl(url)\n    .build(); }")
+        );
+        assert_eq!(None, infer("{ return Foo.of(1); }"));
+        assert_eq!(None, infer("{ return Foo.builder().name(\"x\"); }"));
+        assert_eq!(None, infer("{ Foo foo = new Foo(); return foo; }"));
+        assert_eq!(None, infer("{ ... }"));
+    }
+
     #[test]
     pub fn parameter_from_str() {
         assert_eq!(
             Ok(Parameter {
                 annotations: vec!["@Autowired".to_string()],
                 class: "Foo".to_string(),
-                name: "foo".to_string()
+                name: "foo".to_string(),
+                collection: false,
+                qualifier: None,
             }),
             "  @Autowired  Foo   foo  ".parse()
         );
     }
+
+    #[test]
+    pub fn parameter_with_generic_and_array_types_from_str() {
+        assert_eq!(
+            Ok(Parameter {
+                annotations: vec![],
+                class: "Map<String, Foo>".to_string(),
+                name: "m".to_string(),
+                collection: false,
+                qualifier: None,
+            }),
+            "Map<String, Foo> m".parse()
+        );
+        assert_eq!(
+            Ok(Parameter {
+                annotations: vec!["@Qualifier(\"x\")".to_string()],
+                class: "Map<String, List< Foo >>".to_string(),
+                name: "m".to_string(),
+                collection: false,
+                qualifier: Some(Qualifier::Name("x".to_string())),
+            }),
+            "@Qualifier(\"x\") Map<String, List< Foo >> m".parse()
+        );
+        assert_eq!(
+            Ok(Parameter {
+                annotations: vec![],
+                class: "Foo[]".to_string(),
+                name: "foos".to_string(),
+                collection: false,
+                qualifier: None,
+            }),
+            "Foo[] foos".parse()
+        );
+    }
+
+    #[test]
+    pub fn parse_parameter_lists() {
+        let param = |annotations: &[&str], class: &str, name: &str| Parameter {
+            annotations: annotations.iter().map(|a| a.to_string()).collect(),
+            class: class.to_string(),
+            name: name.to_string(),
+            collection: false,
+            qualifier: None,
+        };
+        assert_eq!(Vec::<Parameter>::new(), parse_parameter_list("  \n "));
+        assert_eq!(
+            vec![
+                param(&[], "Map<String, Foo>", "m"),
+                Parameter {
+                    qualifier: Some(Qualifier::Name("a,b".to_string())),
+                    ..param(&["@Qualifier(value = \"a,b\")"], "Bar", "bar")
+                },
+                param(&[], "Baz", "baz"),
+                param(&["@Value(\"${x}\")"], "String", "x"),
+                param(&[], "Qux[]", "quxes"),
+            ],
+            parse_parameter_list(
+                "Map<String, Foo> m,\n @Qualifier(value = \"a,b\") Bar bar, final Baz baz,\n final @Value(\"${x}\") String x, Qux... quxes"
+            )
+        );
+        // Unparsable parameters are skipped
+        assert_eq!(
+            vec![param(&[], "Foo", "foo")],
+            parse_parameter_list("Foo foo, bar")
+        );
+    }
+
+    #[test]
+    pub fn parameter_with_spaced_annotation_args_from_str() {
+        assert_eq!(
+            Ok(Parameter {
+                annotations: vec!["@Qualifier( \"x y\" )".to_string()],
+                class: "Foo".to_string(),
+                name: "foo".to_string(),
+                collection: false,
+                qualifier: Some(Qualifier::Name("x y".to_string())),
+            }),
+            "@Qualifier( \"x y\" ) Foo foo".parse()
+        );
+        assert_eq!(
+            Ok(Parameter {
+                annotations: vec!["@Qualifier (\"x\")".to_string()],
+                class: "Foo".to_string(),
+                name: "foo".to_string(),
+                collection: false,
+                qualifier: Some(Qualifier::Name("x".to_string())),
+            }),
+            "@Qualifier (\"x\") Foo foo".parse()
+        );
+    }
 }
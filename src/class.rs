@@ -1,21 +1,69 @@
-use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
 
-use super::{autowired::Autowired, bean::Bean, component_type::ComponentType};
+use super::{
+    autowired::{Autowired, Qualifier},
+    bean::Bean,
+    component_type::ComponentType,
+    feign_client::FeignClient,
+};
 use crate::{
-    annotation::{parse_annotation, AnnotationArg},
-    bean::{parse_bean, Parameter},
+    annotation::{parse_annotation, take_balanced, Annotation, AnnotationArg},
+    bean::{parse_bean_with, parse_parameter_list, parse_type, Parameter},
+    component_scan::ScanFilter,
+    feature::{Feature, Features},
+    graph::EdgeKind,
 };
 use derive_builder::Builder;
 use nom::{
-    bytes::complete::{is_not, tag, take_while},
-    character::complete::{alphanumeric1, char, multispace0},
-    combinator::opt,
+    bytes::complete::{is_not, tag, take_while, take_while1},
+    character::complete::{alphanumeric1, char, multispace0, multispace1},
+    combinator::{not, opt},
     error::ErrorKind,
     multi::many0,
-    sequence::delimited,
+    sequence::{delimited, pair, preceded},
     IResult,
 };
 
+/// An outgoing relation of a class, to the name of a class, package or property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    pub target: String,
+    pub kind: EdgeKind,
+    /// The type of the `@Bean` method a dependency is injected into, if not the class itself.
+    pub bean: Option<String>,
+    /// The name of the injected field or parameter.
+    pub name: Option<String>,
+    pub qualifier: Option<Qualifier>,
+    /// Whether a collection takes every bean of the type.
+    pub collection: bool,
+    /// Whether the dependency may be missing, as with `@Autowired(required = false)`.
+    pub optional: bool,
+}
+
+impl Relation {
+    fn new(target: &str, kind: EdgeKind) -> Self {
+        Relation {
+            target: target.to_string(),
+            kind,
+            bean: None,
+            name: None,
+            qualifier: None,
+            collection: false,
+            optional: false,
+        }
+    }
+
+    /// An injection of a constructor or `@Bean` method parameter.
+    fn parameter(parameter: &Parameter, kind: EdgeKind) -> Self {
+        Relation {
+            name: Some(parameter.name.clone()),
+            qualifier: parameter.qualifier.clone(),
+            collection: parameter.collection,
+            ..Relation::new(&parameter.class, kind)
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Builder)]
 pub struct Class {
     package: String,
@@ -34,6 +82,38 @@ pub struct Class {
     bean_defs: Vec<Bean>,
     #[builder(default)]
     interfaces: Vec<String>,
+    #[builder(default)]
+    entity: Option<String>,
+    #[builder(default)]
+    values: Vec<String>,
+    #[builder(default)]
+    feign_client: Option<FeignClient>,
+    #[builder(default)]
+    post_construct: bool,
+    #[builder(default)]
+    pre_destroy: bool,
+    #[builder(default)]
+    java_imports: Vec<String>,
+    #[builder(default)]
+    exclude_filters: Vec<ScanFilter>,
+    #[builder(default)]
+    include_filters: Vec<ScanFilter>,
+    #[builder(default)]
+    annotation_declaration: bool,
+    #[builder(default)]
+    annotations: Vec<String>,
+    #[builder(default)]
+    order: Option<i64>,
+    #[builder(default)]
+    excluded_autoconfigs: Vec<String>,
+    #[builder(default)]
+    base_path: Option<String>,
+    /// The bean name given by the stereotype annotation, as in `@Service("payments")`.
+    #[builder(default)]
+    bean_name: Option<String>,
+    /// The 1-based line of the class declaration, when parsed from source.
+    #[builder(default)]
+    line: Option<usize>,
 }
 
 impl Class {
@@ -72,29 +152,668 @@ impl Class {
     pub fn interfaces(&self) -> &[String] {
         self.interfaces.as_ref()
     }
+
+    /// The entity type managed by a Spring Data repository.
+    pub fn entity(&self) -> Option<&str> {
+        self.entity.as_deref()
+    }
+
+    /// Configuration property keys injected with `@Value`.
+    pub fn values(&self) -> &[String] {
+        self.values.as_ref()
+    }
+
+    /// The remote service of a `@FeignClient` interface.
+    pub fn feign_client(&self) -> Option<&FeignClient> {
+        self.feign_client.as_ref()
+    }
+
+    /// Whether the class has a `@PostConstruct` method.
+    pub fn post_construct(&self) -> bool {
+        self.post_construct
+    }
+
+    /// Whether the class has a `@PreDestroy` method.
+    pub fn pre_destroy(&self) -> bool {
+        self.pre_destroy
+    }
+
+    /// Fully qualified names of the types imported with Java `import` statements.
+    pub fn java_imports(&self) -> &[String] {
+        self.java_imports.as_ref()
+    }
+
+    /// Filters removing classes from the component scans.
+    pub fn exclude_filters(&self) -> &[ScanFilter] {
+        self.exclude_filters.as_ref()
+    }
+
+    /// Filters adding classes to the component scans.
+    pub fn include_filters(&self) -> &[ScanFilter] {
+        self.include_filters.as_ref()
+    }
+
+    /// Whether this declares an annotation type, as in `public @interface RestEndpoint`.
+    /// Its component type is then the stereotype the annotation is meta-annotated with.
+    pub fn annotation_declaration(&self) -> bool {
+        self.annotation_declaration
+    }
+
+    /// Names of the annotations on the class itself, without the `@`.
+    pub fn annotations(&self) -> &[String] {
+        self.annotations.as_ref()
+    }
+
+    /// The precedence given by `@Order` or `@Priority`, lower values first.
+    pub fn order(&self) -> Option<i64> {
+        self.order
+    }
+
+    /// Auto-configurations excluded by `@SpringBootApplication`, by class or by name.
+    pub fn excluded_autoconfigs(&self) -> &[String] {
+        self.excluded_autoconfigs.as_ref()
+    }
+
+    /// The path of a class level `@RequestMapping`, with a leading and no trailing slash.
+    pub fn base_path(&self) -> Option<&str> {
+        self.base_path.as_deref()
+    }
+
+    pub fn bean_name(&self) -> Option<&str> {
+        self.bean_name.as_deref()
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Uses custom qualifiers, annotations meta-annotated with `@Qualifier`, as the qualifier
+    /// of the fields and parameters they are put on. Such a qualifier picks the implementation
+    /// with the same annotation.
+    pub fn with_qualifier_annotations(mut self, qualifiers: &HashSet<String>) -> Self {
+        let custom = |name: &str| {
+            qualifiers
+                .contains(name)
+                .then(|| Qualifier::Annotation(name.to_string()))
+        };
+        for autowire in self.autowires.iter_mut() {
+            if autowire.qualifier().is_none() {
+                let qualifier = autowire.annotations().iter().find_map(|a| custom(a));
+                *autowire = autowire.clone().with_qualifier(qualifier);
+            }
+        }
+        let qualify = |parameters: &[Parameter]| -> Vec<Parameter> {
+            parameters
+                .iter()
+                .map(|p| {
+                    let qualifier = p.qualifier.clone().or_else(|| {
+                        p.annotations
+                            .iter()
+                            .filter_map(|a| parse_annotation(a).ok())
+                            .find_map(|(_, a)| custom(a.name()))
+                    });
+                    Parameter {
+                        qualifier,
+                        ..p.clone()
+                    }
+                })
+                .collect()
+        };
+        self.parameters = qualify(&self.parameters);
+        for bean in self.bean_defs.iter_mut() {
+            *bean = bean.clone().with_parameters(qualify(bean.parameters()));
+        }
+        self
+    }
+
+    /// Gives a class without a known stereotype the component type of a custom stereotype
+    /// it is annotated with, such as `@RestEndpoint` declared with `@RestController`.
+    pub fn with_stereotypes(self, stereotypes: &HashMap<String, ComponentType>) -> Self {
+        if self.component_type.is_some() {
+            return self;
+        }
+        let component_type = self
+            .annotations
+            .iter()
+            .rev()
+            .find_map(|a| stereotypes.get(a))
+            .cloned();
+        Class {
+            component_type,
+            ..self
+        }
+    }
+
+    /// Makes an annotated class without a known stereotype a generic component,
+    /// for when the annotation is a custom stereotype that has not been configured.
+    pub fn assume_component(self) -> Self {
+        if self.component_type.is_none()
+            && !self.annotations.is_empty()
+            && !self.annotation_declaration
+        {
+            Class {
+                component_type: Some(ComponentType::Component),
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+
+    /// The relations of the enabled features, with targets as written in the source.
+    /// Implemented interfaces are included unless implementations are combined with them.
+    pub fn relations(&self, features: &Features) -> impl Iterator<Item = Relation> {
+        let enabled = |feature: Feature| features.contains(&feature);
+        let mut relations = Vec::new();
+        if enabled(Feature::Import) {
+            relations.extend(
+                self.imports
+                    .iter()
+                    .map(|i| Relation::new(i, EdgeKind::Import)),
+            );
+        }
+        if enabled(Feature::ComponentScan) {
+            relations.extend(
+                self.component_scans
+                    .iter()
+                    .map(|p| Relation::new(p, EdgeKind::ComponentScan)),
+            );
+        }
+        if !enabled(Feature::CombineImplAndInterface) {
+            relations.extend(
+                self.interfaces
+                    .iter()
+                    .map(|i| Relation::new(i, EdgeKind::Implements)),
+            );
+        }
+        if enabled(Feature::ConstructorInjection) {
+            relations.extend(
+                self.parameters
+                    .iter()
+                    .map(|p| Relation::parameter(p, EdgeKind::ConstructorInjection)),
+            );
+        }
+        if enabled(Feature::Autowired) {
+            relations.extend(self.autowires.iter().map(|a| Relation {
+                name: Some(a.name().to_string()),
+                qualifier: a.qualifier().cloned(),
+                collection: a.collection(),
+                optional: !a.required(),
+                ..Relation::new(a.class(), EdgeKind::Autowired)
+            }));
+        }
+        if enabled(Feature::Entity) {
+            relations.extend(
+                self.entity
+                    .iter()
+                    .map(|e| Relation::new(e, EdgeKind::Entity)),
+            );
+        }
+        if enabled(Feature::Value) {
+            relations.extend(
+                self.values
+                    .iter()
+                    .map(|v| Relation::new(v, EdgeKind::Value)),
+            );
+        }
+        if enabled(Feature::Bean) {
+            for bean in &self.bean_defs {
+                relations.push(Relation::new(bean.class(), EdgeKind::Bean));
+                // Bean parameters are drawn only when constructor injection is enabled as well
+                if enabled(Feature::ConstructorInjection) {
+                    relations.extend(bean.parameters().iter().map(|p| Relation {
+                        bean: Some(bean.class().to_string()),
+                        ..Relation::parameter(p, EdgeKind::BeanInjection)
+                    }));
+                }
+            }
+        }
+        relations.into_iter()
+    }
+
+    /// The package and name of the class, such as `a.b.c.Foo`.
+    pub fn qualified_name(&self) -> String {
+        if self.package.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.package, self.name)
+        }
+    }
 }
 
-pub fn parse_constructor(class_name: &str, body: &str) -> Option<Vec<Parameter>> {
-    let pos = body.find(&format!("{}(", class_name))?;
-    let body = &body[pos + class_name.len()..];
-
-    let body = body.trim_start_matches('(');
-    let (_, params) = take_while::<_, _, nom::error::Error<_>>(|c: char| c != ')')(body).ok()?;
-    let params = params.trim_end_matches(')').trim();
-    let params: Vec<Parameter> = if !params.is_empty() {
-        params
-            .trim_end_matches(')')
-            .split(',')
-            .filter_map(|p| Parameter::from_str(p).ok())
-            .collect()
+/// Collects the types imported by `import` statements, leaving out static and wildcard imports.
+fn parse_java_imports(header: &str) -> Vec<String> {
+    header
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("import "))
+        .filter(|import| !import.trim_start().starts_with("static "))
+        .map(|import| import.trim().trim_end_matches(';').trim())
+        .filter(|import| !import.ends_with('*'))
+        .map(String::from)
+        .collect()
+}
+
+/// Reads the remote service name and url from a `@FeignClient` annotation.
+fn parse_feign_client(annotation: &Annotation) -> Option<FeignClient> {
+    let name = annotation
+        .get("name")
+        .or_else(|| annotation.value())?
+        .as_string()?;
+    let url = annotation.get("url").and_then(AnnotationArg::as_string);
+    Some(FeignClient::new(name.to_string(), url.map(String::from)))
+}
+
+/// Collects the class names from a single class argument or an array of them.
+fn class_values(arg: &AnnotationArg) -> Vec<String> {
+    match arg.as_array() {
+        Some(values) => values
+            .iter()
+            .filter_map(AnnotationArg::as_class)
+            .map(String::from)
+            .collect(),
+        None => arg.as_class().map(String::from).into_iter().collect(),
+    }
+}
+
+/// Joins two request mapping paths with a single slash, giving a path with a leading
+/// and no trailing slash, such that `/api/` and `users` becomes `/api/users`.
+fn join_paths(base: &str, path: &str) -> String {
+    let segments = [base, path]
+        .into_iter()
+        .map(|p| p.trim_matches('/'))
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>();
+    format!("/{}", segments.join("/"))
+}
+
+/// Collects the strings from a single string argument or an array of them.
+fn string_values(arg: &AnnotationArg) -> Vec<String> {
+    match arg.as_array() {
+        Some(values) => values
+            .iter()
+            .filter_map(AnnotationArg::as_string)
+            .map(String::from)
+            .collect(),
+        None => arg.as_string().map(String::from).into_iter().collect(),
+    }
+}
+
+/// Sorts classes by package and name, so that output does not depend on the order files were read in.
+pub fn sort_classes(classes: &mut [Class]) {
+    classes.sort_by(|a, b| (a.package(), a.name()).cmp(&(b.package(), b.name())));
+}
+
+/// Spring Data repository interfaces that make an extending interface a repository bean.
+const SPRING_DATA_REPOSITORIES: &[&str] = &[
+    "Repository",
+    "CrudRepository",
+    "ListCrudRepository",
+    "PagingAndSortingRepository",
+    "JpaRepository",
+];
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Finds the first occurrence of `keyword` that is not part of a larger identifier.
+fn find_keyword(input: &str, keyword: &str) -> Option<usize> {
+    input
+        .match_indices(keyword)
+        .map(|(pos, _)| pos)
+        .find(|&pos| {
+            let before = input[..pos].chars().next_back();
+            let after = input[pos + keyword.len()..].chars().next();
+            !before.is_some_and(|c| is_identifier_char(c) || c == '.')
+                && !after.is_some_and(is_identifier_char)
+        })
+}
+
+/// Modifiers that may precede the type of a field.
+const MODIFIERS: &[&str] = &[
+    "public",
+    "protected",
+    "private",
+    "static",
+    "final",
+    "transient",
+    "volatile",
+];
+
+/// Parses a single field modifier such as `private` or `final`.
+fn parse_modifier(input: &str) -> IResult<&str, &str> {
+    let (rest, word) = alphanumeric1(input)?;
+    if MODIFIERS.contains(&word) {
+        Ok((rest, word))
     } else {
-        Vec::new()
+        Err(nom::Err::Error(nom::error::make_error(
+            input,
+            ErrorKind::Tag,
+        )))
+    }
+}
+
+/// Finds the first `class` or `interface` keyword, returning it along with its position.
+fn find_declaration(input: &str) -> Option<(&'static str, usize)> {
+    ["class", "interface"]
+        .into_iter()
+        .filter_map(|keyword| find_keyword(input, keyword).map(|pos| (keyword, pos)))
+        .min_by_key(|(_, pos)| *pos)
+}
+
+/// Extracts the property keys from `${...}` placeholders, dropping any default values.
+pub fn property_keys(value: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let placeholder = &rest[..end];
+        let key = placeholder.split(':').next().unwrap_or(placeholder).trim();
+        if !key.is_empty() {
+            keys.push(key.to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    keys
+}
+
+/// Splits a list of types on commas, ignoring the commas between generic type arguments.
+fn split_types(input: &str) -> Vec<&str> {
+    let mut types = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                types.push(input[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    types.push(input[start..].trim());
+    types.retain(|t| !t.is_empty());
+    types
+}
+
+/// Splits a generic type like `JpaRepository<User, Long>` into its name and type arguments.
+fn parse_generic_type(input: &str) -> (&str, Vec<&str>) {
+    let input = input.trim();
+    let Some(start) = input.find('<') else {
+        return (input, Vec::new());
+    };
+    let name = input[..start].trim();
+    let args = input[start + 1..].trim_end().trim_end_matches('>');
+    (name, split_types(args))
+}
+
+/// Collections that Spring fills with every bean of their element type.
+const COLLECTION_TYPES: &[&str] = &["Collection", "Iterable", "List", "Set", "SortedSet"];
+
+/// Annotations injecting a field, Spring's own and the JSR-330 one.
+const INJECT_ANNOTATIONS: &[&str] = &["@Autowired", "@Inject"];
+
+/// Wrappers that inject a single bean of their element type, lazily or optionally.
+pub const WRAPPER_TYPES: &[&str] = &["ObjectFactory", "ObjectProvider", "Optional", "Provider"];
+
+/// What the parser is told beyond the source of a class.
+#[derive(Debug, Clone, Builder)]
+pub struct ParseOptions {
+    /// Annotations treated as the given component types.
+    #[builder(default)]
+    aliases: HashMap<String, ComponentType>,
+    /// Wrappers injecting a single bean of their element type, such as `Provider<Foo>`.
+    #[builder(default = "WRAPPER_TYPES.iter().map(|w| w.to_string()).collect()")]
+    wrapper_types: Vec<String>,
+    /// Whether the implementations of `@Bean` methods are inferred from their bodies.
+    #[builder(default)]
+    infer_implementations: bool,
+}
+
+impl ParseOptions {
+    pub fn aliases(&self) -> &HashMap<String, ComponentType> {
+        &self.aliases
+    }
+
+    /// Replaces the annotations treated as component types.
+    pub fn with_aliases(self, aliases: HashMap<String, ComponentType>) -> Self {
+        ParseOptions { aliases, ..self }
+    }
+
+    /// Sets whether the implementations of `@Bean` methods are inferred from their bodies.
+    pub fn with_infer_implementations(self, infer_implementations: bool) -> Self {
+        ParseOptions {
+            infer_implementations,
+            ..self
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptionsBuilder::default()
+            .build()
+            .expect("all fields have defaults")
+    }
+}
+
+/// Drops a wildcard bound, so that `? extends Foo` and `? super Foo` become `Foo`.
+fn strip_wildcard(type_arg: &str) -> &str {
+    let Some(bounded) = type_arg.trim().strip_prefix('?') else {
+        return type_arg.trim();
+    };
+    let bounded = bounded.trim_start();
+    ["extends", "super"]
+        .into_iter()
+        .find_map(|keyword| bounded.strip_prefix(keyword))
+        .unwrap_or(bounded)
+        .trim()
+}
+
+/// Unwraps one of the `wrappers`, such as `Provider<Foo>`, to the type it injects.
+fn unwrap_wrapper<'a>(class: &'a str, wrappers: &[String]) -> &'a str {
+    let (name, args) = parse_generic_type(class);
+    let simple_name = name.rsplit('.').next().unwrap_or(name);
+    if !wrappers.iter().any(|w| w == simple_name) {
+        return class;
+    }
+    // An unbounded wildcard says nothing about the bean, so keep the wrapper
+    match args.first().map(|a| strip_wildcard(a)) {
+        Some(element) if !element.is_empty() => element,
+        _ => class,
+    }
+}
+
+/// Finds the types of constructor or `@Bean` method parameters, like those of fields.
+fn unwrap_parameters(params: &[Parameter], wrappers: &[String]) -> Vec<Parameter> {
+    params
+        .iter()
+        .map(|p| {
+            let (class, collection) = injected_type(&p.class, wrappers);
+            Parameter {
+                class: class.to_string(),
+                collection,
+                ..p.clone()
+            }
+        })
+        .collect()
+}
+
+/// Finds the type of bean that a field of type `class` is injected with, and whether it
+/// is a collection of all such beans, such as `Foo` in `List<Foo>`, `Map<String, Foo>`
+/// or `Foo[]`.
+fn injected_type<'a>(class: &'a str, wrappers: &[String]) -> (&'a str, bool) {
+    let class = unwrap_wrapper(class, wrappers);
+    if let Some(element) = class.strip_suffix("[]") {
+        return (parse_generic_type(element.trim_end()).0, true);
+    }
+    let (name, args) = parse_generic_type(class);
+    let simple_name = name.rsplit('.').next().unwrap_or(name);
+    let (element, collection) = match simple_name {
+        "Map" => (args.get(1), true),
+        name if COLLECTION_TYPES.contains(&name) => (args.first(), true),
+        _ => (None, false),
     };
+    // An unbounded wildcard says nothing about the bean, so keep the outer type
+    match element.map(|e| strip_wildcard(e)).filter(|e| !e.is_empty()) {
+        Some(element) => (parse_generic_type(element).0, collection),
+        None => (name, false),
+    }
+}
+
+pub fn parse_constructor(class_name: &str, body: &str) -> Option<Vec<Parameter>> {
+    // Only the declared parameter list is read, never calls such as `new Foo(...)`,
+    // `this(...)` or `super(...)` in a body. Comments are already blanked by `read_file`
+    let mut rest = body;
+    let mut first = None;
+    while let Some(pos) = find_keyword(rest, class_name) {
+        let before = &rest[..pos];
+        let previous_word = before
+            .trim_end()
+            .rsplit(|c: char| !is_identifier_char(c))
+            .next();
+        rest = &rest[pos + class_name.len()..];
+        let after = rest.trim_start();
+        if !after.starts_with('(') || previous_word == Some("new") {
+            continue;
+        }
+        let Ok((_, params)) = delimited(char('('), take_balanced('(', ')'), char(')'))(after)
+        else {
+            continue;
+        };
+        // Of several constructors Spring uses the `@Autowired` one, else the first
+        let modifiers = before.rsplit(['{', '}', ';']).next().unwrap_or(before);
+        if modifiers.contains("@Autowired") {
+            return Some(parse_parameter_list(params));
+        }
+        first.get_or_insert(params);
+    }
+    first.map(parse_parameter_list)
+}
+
+/// The declarations ending in `;` directly in the class body, leaving out
+/// everything inside methods, initializer blocks and nested classes.
+fn member_declarations(input: &str) -> Vec<&str> {
+    let mut declarations = Vec::new();
+    let mut chars = input.char_indices();
+    let (mut braces, mut parens) = (0, 0);
+    let mut start = 0;
+    while let Some((i, c)) = chars.next() {
+        match c {
+            // Skip literals, which may hold any of the characters below
+            '"' | '\'' => {
+                while let Some((_, next)) = chars.next() {
+                    if next == '\\' {
+                        chars.next();
+                    } else if next == c {
+                        break;
+                    }
+                }
+            }
+            '(' => parens += 1,
+            ')' => parens -= 1,
+            '{' if parens == 0 => {
+                braces += 1;
+                start = i + 1;
+            }
+            '}' if parens == 0 => {
+                braces -= 1;
+                start = i + 1;
+            }
+            ';' if braces == 1 && parens == 0 => {
+                declarations.push(&input[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    declarations
+}
+
+/// Finds fields declared as `final` without an initializer, which have to be
+/// assigned in a constructor and are therefore injected dependencies.
+pub fn parse_final_fields(body: &str) -> Vec<Parameter> {
+    let mut fields = Vec::new();
+    for statement in member_declarations(body) {
+        let Some(pos) = find_keyword(statement, "final") else {
+            continue;
+        };
+        let modifiers = &statement[..pos];
+        let declaration = &statement[pos + "final".len()..];
 
-    Some(params)
+        // Autowired fields are injected directly rather than through the constructor
+        let is_field = !declaration.contains(['=', '(', ')', '{', '}'])
+            && find_keyword(modifiers, "static").is_none()
+            && !modifiers.contains("@Autowired");
+        if !is_field {
+            continue;
+        }
+        let mut tokens = declaration.split_whitespace().collect::<Vec<_>>();
+        let Some(name) = tokens.pop() else {
+            continue;
+        };
+        if tokens.is_empty() {
+            continue;
+        }
+        fields.push(Parameter::new(
+            modifiers
+                .split_whitespace()
+                .filter(|m| m.starts_with('@'))
+                .map(String::from)
+                .collect(),
+            tokens.join(" "),
+            name.to_string(),
+        ));
+    }
+    fields
 }
 
 pub fn parse_class(input: &str) -> IResult<&str, Class> {
+    parse_class_with_options(input, &ParseOptions::default())
+}
+
+/// The start of the annotations directly preceding `pos`, looking no further back than
+/// `from`. Without semicolons, as in Groovy, a field's annotations may come before them.
+fn annotations_start(input: &str, from: usize, pos: usize) -> usize {
+    let offset = |rest: &str| input.len() - rest.trim_start().len();
+    input[from..pos]
+        .match_indices('@')
+        .map(|(p, _)| from + p)
+        .find(|&start| {
+            let mut rest = &input[start..];
+            while offset(rest) < pos {
+                match parse_annotation(rest) {
+                    Ok((next, _)) => rest = next,
+                    Err(_) => return false,
+                }
+            }
+            offset(rest) == pos
+        })
+        .unwrap_or(pos)
+}
+
+/// The 1-based line that `remaining`, a suffix of `source`, starts on.
+fn line_number(source: &str, remaining: &str) -> usize {
+    source[..source.len() - remaining.len()]
+        .matches('\n')
+        .count()
+        + 1
+}
+
+/// Parses a class, treating the aliases of the options as the given component types.
+/// The source is expected as returned by [`crate::walk::read_file`], with comments blanked.
+pub fn parse_class_with_options<'a>(
+    input: &'a str,
+    options: &ParseOptions,
+) -> IResult<&'a str, Class> {
+    let source = input;
+    let aliases = &options.aliases;
+    let wrappers = &options.wrapper_types;
     let mut class_builder = ClassBuilder::default();
 
     // Package declaration
@@ -102,135 +821,289 @@ pub fn parse_class(input: &str) -> IResult<&str, Class> {
         .find("package")
         .ok_or_else(|| nom::Err::Failure(nom::error::make_error(input, ErrorKind::Fail)))?;
     let input = &input[pos..];
-    let (mut input, between) = delimited(tag("package"), is_not(";"), char(';'))(input)?;
+    // Groovy has no semicolon after the package
+    let (mut input, between) = delimited(
+        pair(tag("package"), multispace1),
+        is_not(";\n"),
+        opt(char(';')),
+    )(input)?;
     let package = between.trim();
     class_builder.package(package.to_string());
+    let header_end = find_declaration(input).map_or(input.len(), |(_, pos)| pos);
+    class_builder.java_imports(parse_java_imports(&input[..header_end]));
 
     // Class level annotations
+    let mut mapper = false;
+    let mut indexed = false;
     if let Some(pos) = input.find('@') {
         let tmp_input = &input[pos..];
-        let (new_input, annotations) = many0(parse_annotation)(tmp_input)?;
+        let (new_input, annotations) =
+            many0(preceded(not(tag("@interface")), parse_annotation))(tmp_input)?;
         input = new_input;
+        class_builder.annotations(annotations.iter().map(|a| a.name().to_string()).collect());
+        class_builder.order(annotations.iter().find_map(Annotation::order));
         for annotation in annotations {
             match annotation.name() {
-                "Import" => {
-                    let imports = annotation.value();
-                    match imports {
-                        Some(AnnotationArg::Class(import)) => {
-                            class_builder.imports(vec![import.clone()])
-                        }
-                        Some(AnnotationArg::Array(values)) => {
-                            let mut classes = Vec::new();
-                            for v in values {
-                                if let AnnotationArg::Class(class) = v {
-                                    classes.push(class.clone());
-                                }
-                            }
-                            class_builder.imports(classes)
-                        }
-                        _ => &mut class_builder,
+                "Import" => match annotation.value() {
+                    Some(arg) => class_builder.imports(class_values(arg)),
+                    None => &mut class_builder,
+                },
+                "SpringBootApplication" => {
+                    let mut excluded: Vec<_> = annotation
+                        .get("exclude")
+                        .map(class_values)
+                        .unwrap_or_default();
+                    if let Some(names) = annotation.get("excludeName") {
+                        excluded.extend(string_values(names));
                     }
+                    class_builder.excluded_autoconfigs(excluded);
+                    class_builder.component_scans(vec![package.to_string()])
                 }
-                "SpringBootApplication" => class_builder.component_scans(vec![package.to_string()]),
                 "ComponentScan" => {
-                    let imports = annotation.value();
-                    match imports {
-                        Some(AnnotationArg::String(path)) => {
-                            class_builder.component_scans(vec![path.clone()])
-                        }
-                        Some(AnnotationArg::Array(values)) if values.is_empty() => {
-                            tracing::info!("Empty component scan");
-                            class_builder.component_scans(vec![package.to_string()])
-                        }
-                        Some(AnnotationArg::Array(values)) => {
-                            let mut paths = Vec::new();
-                            for v in values {
-                                if let AnnotationArg::String(path) = v {
-                                    paths.push(path.clone());
-                                }
-                            }
-                            class_builder.component_scans(paths)
-                        }
-                        _ => &mut class_builder,
+                    let filters = |key| {
+                        let arg = annotation.get(key);
+                        arg.and_then(AnnotationArg::as_array)
+                            .unwrap_or(arg.map(std::slice::from_ref).unwrap_or_default())
+                            .iter()
+                            .filter_map(AnnotationArg::as_annotation)
+                            .map(ScanFilter::from_annotation)
+                            .collect::<Vec<_>>()
+                    };
+                    class_builder.exclude_filters(filters("excludeFilters"));
+                    class_builder.include_filters(filters("includeFilters"));
+                    let packages = annotation
+                        .value()
+                        .or_else(|| annotation.get("basePackages"))
+                        .map(string_values)
+                        .unwrap_or_default();
+                    if packages.is_empty() {
+                        tracing::info!("Empty component scan");
+                        class_builder.component_scans(vec![package.to_string()])
+                    } else {
+                        class_builder.component_scans(packages)
                     }
                 }
+                "FeignClient" => class_builder.feign_client(parse_feign_client(&annotation)),
+                "RequestMapping" => {
+                    let path = annotation
+                        .value()
+                        .or_else(|| annotation.get("path"))
+                        .map(string_values)
+                        .and_then(|paths| paths.into_iter().next());
+                    class_builder.base_path(path.map(|p| join_paths("", &p)))
+                }
+                "Mapper" => {
+                    mapper = true;
+                    &mut class_builder
+                }
+                "Indexed" => {
+                    indexed = true;
+                    &mut class_builder
+                }
                 _ => &mut class_builder,
             };
-            // Set component type
-            match annotation.name() {
-                "SpringBootApplication" => {
-                    class_builder.component_type(Some(ComponentType::SpringBootApplication))
+            // Set component type, mappers are only components when they are interfaces
+            let component_type = match annotation.name() {
+                "Mapper" => None,
+                name => {
+                    ComponentType::from_annotation_name(name).or_else(|| aliases.get(name).cloned())
                 }
-                "Configuration" => class_builder.component_type(Some(ComponentType::Configuration)),
-                "Controller" => class_builder.component_type(Some(ComponentType::Controller)),
-                "RestController" => class_builder.component_type(Some(ComponentType::Controller)),
-                "Service" => class_builder.component_type(Some(ComponentType::Service)),
-                "Repository" => class_builder.component_type(Some(ComponentType::Repository)),
-                "Component" => class_builder.component_type(Some(ComponentType::Component)),
-                _ => &mut class_builder,
             };
+            if let Some(component_type) = component_type {
+                // The value of a Feign client names the remote service rather than the bean
+                if component_type != ComponentType::FeignClient {
+                    if let Some(AnnotationArg::String(name)) = annotation.value() {
+                        class_builder.bean_name(Some(name.clone()));
+                    }
+                }
+                class_builder.component_type(Some(component_type));
+            }
         }
     }
 
-    // Class name
-    let class_start = input
-        .find("class")
+    // Class or interface name
+    let (keyword, class_start) = find_declaration(input)
         .ok_or_else(|| nom::Err::Failure(nom::error::make_error(input, ErrorKind::Fail)))?;
-    let input = &input[class_start + "class".len()..];
+    let annotation_declaration = keyword == "interface" && input[..class_start].ends_with('@');
+    class_builder.annotation_declaration(annotation_declaration);
+    let input = &input[class_start + keyword.len()..];
     let (input, _) = multispace0(input)?;
+    class_builder.line(Some(line_number(source, input)));
     let (input, name) = take_while(|c: char| c.is_alphanumeric())(input)?;
     class_builder.name(name.to_string());
 
-    // Find interfaces this class extends
-    let interfaces_start = input.find("implements");
-    if let Some(pos) = interfaces_start {
-        let input = &input[pos + "implements".len()..];
-        let (input, _) = multispace0(input)?;
-        let (_, interfaces) = take_while(|c: char| c.is_alphanumeric())(input)?;
-        let interfaces = interfaces
-            .split(',')
-            .map(|s| s.trim().to_string())
+    // MyBatis mappers are interfaces, with implementations generated at runtime
+    if mapper && keyword == "interface" {
+        class_builder.component_type(Some(ComponentType::Mapper));
+    }
+
+    // Annotations marked with @Indexed are stereotypes even without a more specific one
+    if indexed && annotation_declaration && !matches!(class_builder.component_type, Some(Some(_))) {
+        class_builder.component_type(Some(ComponentType::Component));
+    }
+
+    // Spring Data repositories are interfaces extending one of the repository interfaces
+    if keyword == "interface" {
+        let header = &input[..input.find('{').unwrap_or(input.len())];
+        if let Some(pos) = find_keyword(header, "extends") {
+            let parents = split_types(&header[pos + "extends".len()..]);
+            let (parent, type_args) = parse_generic_type(parents.first().unwrap_or(&""));
+            if SPRING_DATA_REPOSITORIES.contains(&parent) {
+                class_builder.component_type(Some(ComponentType::Repository));
+                class_builder.entity(type_args.first().map(|e| e.to_string()));
+            }
+        }
+    }
+
+    // Find interfaces this class implements. Generic arguments are dropped,
+    // since nodes and injected types are identified by their plain type name.
+    let header = &input[..input.find('{').unwrap_or(input.len())];
+    if let Some(pos) = find_keyword(header, "implements") {
+        let interfaces = split_types(&header[pos + "implements".len()..])
+            .into_iter()
+            .map(|i| parse_generic_type(i).0.to_string())
             .collect();
         class_builder.interfaces(interfaces);
     }
 
-    // Find constructor
-    let parameters = parse_constructor(name, input);
-    if let Some(parameters) = parameters {
-        class_builder.parameters(parameters);
+    // Find constructor, falling back to final fields for parameters it does not list
+    let mut parameters = parse_constructor(name, input).unwrap_or_default();
+    for field in parse_final_fields(input) {
+        if !parameters.iter().any(|p| p.name == field.name) {
+            parameters.push(field);
+        }
     }
+    class_builder.parameters(unwrap_parameters(&parameters, wrappers));
 
     // Autowire
     let mut autowire_start = input;
     let mut autowires = Vec::new();
-    while let Some(pos) = autowire_start.find("@Autowired") {
+    while let Some(pos) = INJECT_ANNOTATIONS
+        .iter()
+        .filter_map(|a| find_keyword(autowire_start, a))
+        .min()
+    {
         autowire_start = &autowire_start[pos..];
-        let (input, (class, name)) = delimited(
-            tag("@Autowired"),
-            |input| {
-                let (input, _) =
-                    many0(delimited(multispace0, parse_annotation, multispace0))(input)?;
-                let (input, _) = multispace0(input)?;
-                let (input, class) = alphanumeric1(input)?;
-                let (input, _) = multispace0(input)?;
-                let (input, name) = alphanumeric1(input)?;
-                Ok((input, (class, name)))
-            },
-            opt(char(';')),
-        )(autowire_start)?;
-        autowires.push(Autowired::new(class.to_string(), name.to_string()));
+        let parsed = pair(parse_annotation, |input| {
+            let (input, others) =
+                many0(delimited(multispace0, parse_annotation, multispace0))(input)?;
+            let (input, _) = many0(delimited(multispace0, parse_modifier, multispace0))(input)?;
+            let (input, _) = multispace0(input)?;
+            let (input, class) = parse_type(input)?;
+            let (input, _) = multispace0(input)?;
+            let (input, name) = take_while1(is_identifier_char)(input)?;
+            let (input, _) = multispace0(input)?;
+            // A setter or other method injecting its parameters
+            let (input, params) =
+                opt(delimited(char('('), take_balanced('(', ')'), char(')')))(input)?;
+            let (input, _) = opt(char(';'))(input)?;
+            Ok((input, (others, class, name, params)))
+        })(autowire_start);
+        // Skip what cannot be understood, such as autowired constructors, rather than the whole class
+        let Ok((input, (annotation, (others, class, name, params)))) = parsed else {
+            tracing::debug!("Skipping unparseable injection in {}", name);
+            autowire_start = &autowire_start["@".len()..];
+            continue;
+        };
+        let qualifier = others
+            .iter()
+            .find(|a| a.name() == "Qualifier" || a.name() == "Named")
+            .and_then(|a| {
+                a.value()
+                    .and_then(AnnotationArg::as_string)
+                    .map(|name| Qualifier::Name(name.to_string()))
+            });
+        let required = annotation.get("required") != Some(&AnnotationArg::Boolean(false));
+        let line = line_number(source, autowire_start);
+        if let Some(params) = params {
+            for param in parse_parameter_list(params) {
+                let (class, collection) = injected_type(&param.class, wrappers);
+                let annotations = param
+                    .annotations
+                    .iter()
+                    .filter_map(|a| parse_annotation(a).ok())
+                    .map(|(_, a)| a.name().to_string())
+                    .collect();
+                autowires.push(
+                    Autowired::new(class.to_string(), param.name.clone())
+                        .with_required(required)
+                        .with_collection(collection)
+                        .with_qualifier(param.qualifier().cloned().or_else(|| qualifier.clone()))
+                        .with_annotations(annotations)
+                        .with_line(line),
+                );
+            }
+            autowire_start = input;
+            continue;
+        }
+        // Groovy fields declared with `def` have no type to inject by
+        if class == "def" {
+            tracing::debug!("Skipping untyped injection {}", name);
+            autowire_start = input;
+            continue;
+        }
+        let (class, collection) = injected_type(class, wrappers);
+        autowires.push(
+            Autowired::new(class.to_string(), name.to_string())
+                .with_required(required)
+                .with_collection(collection)
+                .with_qualifier(qualifier)
+                .with_annotations(others.iter().map(|a| a.name().to_string()).collect())
+                .with_line(line),
+        );
         autowire_start = input;
     }
     class_builder.autowires(autowires);
 
+    // Property values
+    let mut value_start = input;
+    let mut values = Vec::new();
+    while let Some(pos) = find_keyword(value_start, "@Value") {
+        value_start = &value_start[pos..];
+        let Ok((input, annotation)) = parse_annotation(value_start) else {
+            tracing::debug!("Skipping unparseable @Value in {}", name);
+            value_start = &value_start["@Value".len()..];
+            continue;
+        };
+        if let Some(AnnotationArg::String(value)) = annotation.value() {
+            values.extend(property_keys(value));
+        }
+        value_start = input;
+    }
+    class_builder.values(values);
+
+    // Lifecycle callbacks
+    class_builder.post_construct(find_keyword(input, "@PostConstruct").is_some());
+    class_builder.pre_destroy(find_keyword(input, "@PreDestroy").is_some());
+
     // Beans
     let mut beans_start = input;
     let mut beans = Vec::new();
-    while let Some(pos) = beans_start.find("@Bean") {
-        beans_start = &beans_start[pos..];
-        let (input, bean) = parse_bean(beans_start)?;
+    while let Some(pos) = find_keyword(beans_start, "@Bean") {
+        // Start from the first annotation of the method, which may precede @Bean
+        let method_start = beans_start[..pos]
+            .rfind([';', '{', '}'])
+            .map_or(0, |p| p + 1);
+        let first_annotation = annotations_start(beans_start, method_start, pos);
+        let method = &beans_start[first_annotation..];
+        let Ok((input, bean)) = parse_bean_with(options.infer_implementations)(method) else {
+            tracing::warn!("Skipping unparseable @Bean method in {}", name);
+            beans_start = &beans_start[pos + "@Bean".len()..];
+            continue;
+        };
         beans_start = input;
-        beans.push(bean);
+        // A void method registers no bean, Spring rejects it at startup
+        if bean.class() == "void" {
+            tracing::warn!("Skipping @Bean method {} returning void", bean.name());
+            continue;
+        }
+        if bean.class() == "def" {
+            tracing::warn!("Skipping @Bean method {} of unknown type", bean.name());
+            continue;
+        }
+        let parameters = unwrap_parameters(bean.parameters(), wrappers);
+        let bean = bean.with_parameters(parameters);
+        beans.push(bean.with_line(line_number(source, method)));
     }
     class_builder.bean_defs(beans);
 
@@ -247,13 +1120,17 @@ mod tests {
     use std::vec;
 
     use crate::{
-        autowired::Autowired,
+        autowired::{Autowired, Qualifier},
         bean::{Bean, Parameter},
-        class::{parse_class, Class},
+        class::{parse_class, parse_class_with_options, Class, ParseOptions, ParseOptionsBuilder},
+        component_scan::{FilterType, ScanFilter},
         component_type::ComponentType,
+        feign_client::FeignClient,
+        graph::EdgeKind,
+        walk::normalize_source,
     };
 
-    use super::parse_constructor;
+    use super::{join_paths, parse_constructor, property_keys};
 
     #[test]
     pub fn parse_class_test() {
@@ -269,11 +1146,15 @@ mod tests {
                     parameters: vec![Parameter {
                         annotations: vec!["@Arg".to_string()],
                         class: "Arg".to_string(),
-                        name: "arg".to_string()
+                        name: "arg".to_string(),
+                        collection: false,
+                        qualifier: None,
                     }],
                     autowires: vec![
-                        Autowired::new("Foo".to_string(), "foo".to_string()),
+                        Autowired::new("Foo".to_string(), "foo".to_string()).with_line(8),
                         Autowired::new("FooBean".to_string(), "fooBean".to_string())
+                            .with_annotations(vec!["NotNull".to_string()])
+                            .with_line(10)
                     ],
                     bean_defs: vec![Bean::new(
                         "MyBean".to_string(),
@@ -281,10 +1162,32 @@ mod tests {
                         vec![Parameter {
                             annotations: vec!["@Autowired".to_string(), "@NotNull".to_string()],
                             class: "FooBean".to_string(),
-                            name: "fooBean".to_string()
+                            name: "fooBean".to_string(),
+                            collection: false,
+                            qualifier: None,
                         }]
-                    )],
-                    interfaces: vec!["IFoo".to_string()]
+                    )
+                    .with_line(9)],
+                    interfaces: vec!["IFoo".to_string()],
+                    entity: None,
+                    values: vec![],
+                    feign_client: None,
+                    post_construct: false,
+                    pre_destroy: false,
+                    java_imports: vec![],
+                    exclude_filters: vec![],
+                    include_filters: vec![],
+                    annotation_declaration: false,
+                    annotations: vec![
+                        "Component".to_string(),
+                        "Import".to_string(),
+                        "ComponentScan".to_string()
+                    ],
+                    order: None,
+                    excluded_autoconfigs: vec![],
+                    base_path: None,
+                    bean_name: None,
+                    line: Some(7),
                 }
             )),
             parse_class(
@@ -306,6 +1209,1071 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn parse_jpa_repository_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            import org.springframework.data.jpa.repository.JpaRepository;
+
+            public interface UserRepository extends JpaRepository<User, Long> {
+                List<User> findByName(String name);
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("UserRepository", class.name());
+        assert_eq!(Some(&ComponentType::Repository), class.component_type());
+        assert_eq!(Some("User"), class.entity());
+    }
+
+    #[test]
+    pub fn parse_value_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Service
+            public class Foo {
+                @Value("${app.timeout}")
+                private int timeout;
+
+                @Value("${app.name:default}")
+                private String name;
+
+                Foo(@Value("${app.retries}") int retries) {}
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                "app.timeout".to_string(),
+                "app.name".to_string(),
+                "app.retries".to_string()
+            ],
+            class.values()
+        );
+    }
+
+    #[test]
+    pub fn parse_feign_client_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @FeignClient(name = "billing", url = "${billing.url}")
+            public interface BillingClient {
+                @GetMapping("/invoices")
+                List<Invoice> invoices();
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("BillingClient", class.name());
+        assert_eq!(Some(&ComponentType::FeignClient), class.component_type());
+        assert_eq!(
+            Some(&FeignClient::new(
+                "billing".to_string(),
+                Some("${billing.url}".to_string())
+            )),
+            class.feign_client()
+        );
+    }
+
+    #[test]
+    pub fn parse_import_spellings_test() {
+        let imports = |annotation: &str| {
+            let source = format!("package a.b.c;\n\n{}\npublic class Foo {{}}", annotation);
+            let (_, class) = parse_class(&source).unwrap();
+            class.imports().to_vec()
+        };
+        let expected = vec!["A".to_string()];
+        assert_eq!(expected, imports("@Import(A.class)"));
+        assert_eq!(expected, imports("@Import({A.class})"));
+        assert_eq!(expected, imports("@Import(value = {A.class})"));
+        assert_eq!(expected, imports("@Import(value = A.class)"));
+
+        let expected = vec!["A".to_string(), "B".to_string()];
+        assert_eq!(expected, imports("@Import({A.class, B.class})"));
+        assert_eq!(expected, imports("@Import(value = {A.class, B.class})"));
+        assert_eq!(
+            expected,
+            imports("@Import({\n    A.class,\n    B.class\n})")
+        );
+    }
+
+    #[test]
+    pub fn parse_generic_interfaces_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Component
+            public class Baz implements Foo<A, B>, Bar {
+                Baz() {}
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["Foo".to_string(), "Bar".to_string()],
+            class.interfaces()
+        );
+    }
+
+    #[test]
+    pub fn parse_lifecycle_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Component
+            public class Foo {
+                @PostConstruct
+                public void init() {}
+            }
+            "#,
+        )
+        .unwrap();
+        assert!(class.post_construct());
+        assert!(!class.pre_destroy());
+    }
+
+    #[test]
+    pub fn parse_class_with_bean_name_test() {
+        let parse = |annotation: &str| {
+            let source = format!("package a;\n\n{}\npublic class Foo {{}}\n", annotation);
+            parse_class(&source)
+                .unwrap()
+                .1
+                .bean_name()
+                .map(String::from)
+        };
+        assert_eq!(Some("foo".to_string()), parse("@Service(\"foo\")"));
+        assert_eq!(
+            Some("foo".to_string()),
+            parse("@Component(value = \"foo\")")
+        );
+        assert_eq!(None, parse("@Service"));
+        assert_eq!(None, parse("@FeignClient(\"billing\")"));
+    }
+
+    #[test]
+    pub fn parse_class_with_alias_test() {
+        let source = r#"
+            package a.b.c;
+
+            @MyService
+            public class Foo {}
+            "#;
+        let (_, class) = parse_class(source).unwrap();
+        assert_eq!(None, class.component_type());
+
+        let aliases = vec![("MyService".to_string(), ComponentType::Service)]
+            .into_iter()
+            .collect();
+        let options = ParseOptionsBuilder::default()
+            .aliases(aliases)
+            .build()
+            .unwrap();
+        let (_, class) = parse_class_with_options(source, &options).unwrap();
+        assert_eq!(Some(&ComponentType::Service), class.component_type());
+    }
+
+    #[test]
+    pub fn parse_autowired_with_modifiers_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Service
+            public class FooService {
+                @Autowired private Foo foo;
+                @Autowired
+                private final Bar bar;
+                @Autowired Baz baz;
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                Autowired::new("Foo".to_string(), "foo".to_string()).with_line(6),
+                Autowired::new("Bar".to_string(), "bar".to_string()).with_line(7),
+                Autowired::new("Baz".to_string(), "baz".to_string()).with_line(9),
+            ],
+            class.autowires()
+        );
+        assert!(class.parameters().is_empty());
+    }
+
+    #[test]
+    pub fn parse_autowired_setter_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Service
+            public class FooService {
+                @Autowired
+                public void setBar(Bar bar) {
+                    this.bar = bar;
+                }
+                @Inject
+                void init(@Qualifier("main") Baz baz, List<Qux> quxes) {}
+                @Autowired Foo foo;
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                Autowired::new("Bar".to_string(), "bar".to_string()).with_line(6),
+                Autowired::new("Baz".to_string(), "baz".to_string())
+                    .with_qualifier(Some(Qualifier::Name("main".to_string())))
+                    .with_annotations(vec!["Qualifier".to_string()])
+                    .with_line(10),
+                Autowired::new("Qux".to_string(), "quxes".to_string())
+                    .with_collection(true)
+                    .with_line(10),
+                Autowired::new("Foo".to_string(), "foo".to_string()).with_line(12),
+            ],
+            class.autowires()
+        );
+    }
+
+    #[test]
+    pub fn parse_java_imports_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            import x.y.Foo;
+            import static x.y.Constants.LIMIT;
+            import x.z.*;
+
+            @Service
+            public class Bar {}
+            "#,
+        )
+        .unwrap();
+        assert_eq!(vec!["x.y.Foo".to_string()], class.java_imports());
+        assert_eq!("a.b.c.Bar", class.qualified_name());
+    }
+
+    #[test]
+    pub fn parse_component_scan_filters_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b;
+
+            @Configuration
+            @ComponentScan(
+                basePackages = "a.b",
+                excludeFilters = {
+                    @ComponentScan.Filter(type = FilterType.REGEX, pattern = "a\\.b\\.test\\..*"),
+                    @ComponentScan.Filter(type = FilterType.ASSIGNABLE_TYPE, classes = LegacyService.class)
+                },
+                includeFilters = @ComponentScan.Filter(Marker.class)
+            )
+            public class AppConfig {}
+            "#,
+        )
+        .unwrap();
+        assert_eq!(vec!["a.b".to_string()], class.component_scans());
+        assert_eq!(
+            vec![
+                ScanFilter::new(
+                    FilterType::Regex,
+                    vec![],
+                    vec!["a\\.b\\.test\\..*".to_string()]
+                ),
+                ScanFilter::new(
+                    FilterType::AssignableType,
+                    vec!["LegacyService".to_string()],
+                    vec![]
+                ),
+            ],
+            class.exclude_filters()
+        );
+        assert_eq!(
+            vec![ScanFilter::new(
+                FilterType::Annotation,
+                vec!["Marker".to_string()],
+                vec![]
+            )],
+            class.include_filters()
+        );
+    }
+
+    #[test]
+    pub fn parse_optional_autowired_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Service
+            public class FooService {
+                @Autowired(required = false) Foo foo;
+                @Autowired(required = true) private Bar bar;
+            }
+            "#,
+        )
+        .unwrap();
+        let autowires = class.autowires();
+        assert_eq!(
+            ("Foo", "foo", false),
+            (
+                autowires[0].class(),
+                autowires[0].name(),
+                autowires[0].required()
+            )
+        );
+        assert_eq!(
+            ("Bar", "bar", true),
+            (
+                autowires[1].class(),
+                autowires[1].name(),
+                autowires[1].required()
+            )
+        );
+    }
+
+    #[test]
+    pub fn parse_functional_bean_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Configuration
+            public class FooConfig {
+                @Bean
+                public Supplier<Foo> fooSupplier() {
+                    return Foo::new;
+                }
+
+                @BeanPostProcessorMarker
+                @Bean
+                public Function<Bar, Baz> bazFunction() {
+                    return bar -> new Baz(bar);
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        let beans = class
+            .bean_defs()
+            .iter()
+            .map(|b| (b.class(), b.name()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("Supplier<Foo>", "fooSupplier"),
+                ("Function<Bar, Baz>", "bazFunction")
+            ],
+            beans
+        );
+    }
+
+    #[test]
+    pub fn parse_mapper_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Mapper
+            public interface UserMapper {
+                @Select("SELECT * FROM users WHERE id = #{id}")
+                User findById(long id);
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("UserMapper", class.name());
+        assert_eq!(Some(&ComponentType::Mapper), class.component_type());
+    }
+
+    #[test]
+    pub fn parse_primary_bean_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Configuration
+            public class FooConfig {
+                @Primary
+                @Bean
+                public Foo foo() {
+                    return new Foo();
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(1, class.bean_defs().len());
+        assert_eq!("foo", class.bean_defs()[0].name());
+        assert!(class.bean_defs()[0].primary());
+    }
+
+    #[test]
+    pub fn parse_stereotype_declaration_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            import java.lang.annotation.Retention;
+
+            @Retention(RetentionPolicy.RUNTIME)
+            @RestController
+            public @interface RestEndpoint {
+                String value() default "";
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("RestEndpoint", class.name());
+        assert!(class.annotation_declaration());
+        assert_eq!(Some(&ComponentType::RestController), class.component_type());
+
+        let (_, class) = parse_class("package a;\n\n@Indexed\n@interface Marker {}\n").unwrap();
+        assert_eq!("Marker", class.name());
+        assert_eq!(Some(&ComponentType::Component), class.component_type());
+
+        let (_, class) = parse_class("package a;\n\npublic @interface Plain {}\n").unwrap();
+        assert_eq!("Plain", class.name());
+        assert_eq!(None, class.component_type());
+    }
+
+    #[test]
+    pub fn parse_collection_autowired_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Service
+            public class FooService {
+                @Autowired
+                private List<Foo> foos;
+                @Autowired
+                private Map<String, Bar> barsByName;
+                @Autowired
+                private Baz baz;
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            &[
+                Autowired::new("Foo".to_string(), "foos".to_string())
+                    .with_collection(true)
+                    .with_line(6),
+                Autowired::new("Bar".to_string(), "barsByName".to_string())
+                    .with_collection(true)
+                    .with_line(8),
+                Autowired::new("Baz".to_string(), "baz".to_string()).with_line(10),
+            ],
+            class.autowires()
+        );
+    }
+
+    #[test]
+    pub fn parse_wildcard_autowired_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Service
+            public class FooService {
+                @Autowired
+                private Provider<? extends Foo> fooProvider;
+                @Autowired
+                private List<? super Bar> bars;
+                @Autowired
+                private Map<String, ? extends Handler<?>> handlers;
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            &[
+                Autowired::new("Foo".to_string(), "fooProvider".to_string()).with_line(6),
+                Autowired::new("Bar".to_string(), "bars".to_string())
+                    .with_collection(true)
+                    .with_line(8),
+                Autowired::new("Handler".to_string(), "handlers".to_string())
+                    .with_collection(true)
+                    .with_line(10),
+            ],
+            class.autowires()
+        );
+    }
+
+    #[test]
+    pub fn parse_wrapped_autowired_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Service
+            public class FooService {
+                @Inject
+                private Provider<Foo> provider;
+                @Autowired
+                private ObjectProvider<Foo> objectProvider;
+                @Autowired
+                private ObjectFactory<Foo> objectFactory;
+                @Autowired
+                private Optional<Foo> optional;
+                @Inject
+                @Named("bar")
+                private Bar bar;
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            &[
+                Autowired::new("Foo".to_string(), "provider".to_string()).with_line(6),
+                Autowired::new("Foo".to_string(), "objectProvider".to_string()).with_line(8),
+                Autowired::new("Foo".to_string(), "objectFactory".to_string()).with_line(10),
+                Autowired::new("Foo".to_string(), "optional".to_string()).with_line(12),
+                Autowired::new("Bar".to_string(), "bar".to_string())
+                    .with_qualifier(Some(Qualifier::Name("bar".to_string())))
+                    .with_annotations(vec!["Named".to_string()])
+                    .with_line(14),
+            ],
+            class.autowires()
+        );
+    }
+
+    #[test]
+    pub fn parse_wrapped_parameters_test() {
+        let source = r#"
+            package a.b.c;
+
+            @Configuration
+            public class FooConfig {
+                FooConfig(Provider<Foo> foo, Lazy<Bar> bar) {}
+
+                @Bean
+                public Baz baz(Optional<Foo> foo, ObjectProvider<? extends Qux> qux) { ... }
+            }
+            "#;
+        let classes = |class: &Class| {
+            let params = class.parameters().iter();
+            let bean_params = class.bean_defs()[0].parameters().iter();
+            params
+                .chain(bean_params)
+                .map(|p| p.class.clone())
+                .collect::<Vec<_>>()
+        };
+        let (_, class) = parse_class(source).unwrap();
+        assert_eq!(vec!["Foo", "Lazy", "Foo", "Qux"], classes(&class));
+
+        let mut wrappers: Vec<_> = ParseOptions::default().wrapper_types.clone();
+        wrappers.push("Lazy".to_string());
+        let options = ParseOptionsBuilder::default()
+            .wrapper_types(wrappers)
+            .build()
+            .unwrap();
+        let (_, class) = parse_class_with_options(source, &options).unwrap();
+        assert_eq!(vec!["Foo", "Bar", "Foo", "Qux"], classes(&class));
+    }
+
+    #[test]
+    pub fn parse_collection_parameters_test() {
+        let source = r#"
+            package a.b.c;
+
+            @Configuration
+            public class FooConfig {
+                FooConfig(List<Foo> foos, Handler... handlers, Bar bar) {}
+
+                @Bean
+                public Baz baz(Map<String, Qux> quxes, Qux[] more) { ... }
+            }
+            "#;
+        let (_, class) = parse_class(source).unwrap();
+        let params: Vec<_> = class
+            .parameters()
+            .iter()
+            .chain(class.bean_defs()[0].parameters())
+            .map(|p| (p.class.as_str(), p.collection))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Foo", true),
+                ("Handler", true),
+                ("Bar", false),
+                ("Qux", true),
+                ("Qux", true)
+            ],
+            params
+        );
+    }
+
+    #[test]
+    pub fn relations_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Configuration
+            @Import(Bar.class)
+            @ComponentScan("a.b.d")
+            public class Foo implements Runnable {
+                @Autowired(required = false) Baz baz;
+                @Value("${foo.url}") String url;
+
+                Foo(@Qualifier("main") Qux qux) {}
+
+                @Bean
+                public MyBean myBean(List<Dep> deps) { ... }
+            }
+            "#,
+        )
+        .unwrap();
+        let relations = |features: &str| {
+            class
+                .relations(&features.parse().unwrap())
+                .map(|r| (r.target, r.kind))
+                .collect::<Vec<_>>()
+        };
+        let s = String::from;
+        assert_eq!(
+            vec![
+                (s("Bar"), EdgeKind::Import),
+                (s("a.b.d"), EdgeKind::ComponentScan),
+                (s("Qux"), EdgeKind::ConstructorInjection),
+                (s("Baz"), EdgeKind::Autowired),
+                (s("foo.url"), EdgeKind::Value),
+                (s("MyBean"), EdgeKind::Bean),
+                (s("Dep"), EdgeKind::BeanInjection),
+            ],
+            relations("all")
+        );
+        assert_eq!(
+            vec![
+                (s("Runnable"), EdgeKind::Implements),
+                (s("Baz"), EdgeKind::Autowired),
+                (s("MyBean"), EdgeKind::Bean)
+            ],
+            relations("autowired,bean")
+        );
+
+        let features = "all".parse().unwrap();
+        let relations: Vec<_> = class.relations(&features).collect();
+        assert!(relations[3].optional);
+        assert_eq!(Some("baz"), relations[3].name.as_deref());
+        assert_eq!(
+            Some(&Qualifier::Name("main".to_string())),
+            relations[2].qualifier.as_ref()
+        );
+        assert_eq!(Some("MyBean"), relations[6].bean.as_deref());
+        assert!(relations[6].collection);
+    }
+
+    #[test]
+    pub fn parse_void_bean_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Configuration
+            public class FooConfig {
+                @Bean
+                public void configure() {
+                }
+
+                @Bean
+                public Foo foo() {
+                    return new Foo();
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        let beans: Vec<_> = class.bean_defs().iter().map(|b| b.class()).collect();
+        assert_eq!(vec!["Foo"], beans);
+    }
+
+    #[test]
+    pub fn parse_decompiled_class_test() {
+        let source = normalize_source(
+            r#"
+            //
+            // Source code recreated from a .class file by IntelliJ IDEA
+            // (powered by FernFlower decompiler)
+            //
+
+            package a.b.c;
+
+            import org.springframework.stereotype.Service;
+
+            /* $FF: renamed from: a.b.c.x */
+            @Service
+            public class FooService {
+                // $FF: synthetic field
+                static Class class$0;
+                @Autowired
+                private Bar bar;
+                @Autowired
+                private ;
+
+                @Bean
+                public <T extends> T broken() {
+                    return null;
+                }
+
+                @Bean
+                public Baz baz(/* $FF: renamed from: a */ Qux qux) {
+                    return new Baz(qux);
+                }
+            }
+            "#,
+        );
+        let (_, class) = parse_class(&source).unwrap();
+        assert_eq!(Some(ComponentType::Service), class.component_type);
+        let autowired: Vec<_> = class.autowires().iter().map(|a| a.class()).collect();
+        assert_eq!(vec!["Bar"], autowired);
+        let beans: Vec<_> = class.bean_defs().iter().map(|b| b.class()).collect();
+        assert_eq!(vec!["Baz"], beans);
+    }
+
+    #[test]
+    pub fn parse_excluded_autoconfigs_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @SpringBootApplication(
+                exclude = {DataSourceAutoConfiguration.class, SecurityAutoConfiguration.class},
+                excludeName = "org.example.FooAutoConfiguration"
+            )
+            public class Application {
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                "DataSourceAutoConfiguration",
+                "SecurityAutoConfiguration",
+                "org.example.FooAutoConfiguration"
+            ],
+            class.excluded_autoconfigs()
+        );
+        assert_eq!(vec!["a.b.c"], class.component_scans());
+    }
+
+    #[test]
+    pub fn parse_base_path_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @RestController
+            @RequestMapping("/api/v1/")
+            public class FooController {
+                @GetMapping("users")
+                public List<User> users() {
+                    return List.of();
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(Some("/api/v1"), class.base_path());
+        assert_eq!(
+            "/api/v1/users",
+            join_paths(class.base_path().unwrap(), "users")
+        );
+        assert_eq!("/api/users", join_paths("/api", "/users"));
+        assert_eq!("/", join_paths("", "/"));
+    }
+
+    #[test]
+    pub fn parse_order_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Component
+            @Order(5)
+            public class FooFilter implements Filter {
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(Some(5), class.order());
+    }
+
+    #[test]
+    fn property_keys_works() {
+        assert_eq!(vec!["a.b".to_string()], property_keys("${a.b}"));
+        assert_eq!(vec!["a.b".to_string()], property_keys("${a.b:42}"));
+        assert_eq!(
+            vec!["host".to_string(), "port".to_string()],
+            property_keys("http://${host}:${port:80}/")
+        );
+        assert!(property_keys("#{systemProperties.x}").is_empty());
+    }
+
+    #[test]
+    pub fn parse_final_fields_test() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Service
+            public class FooService {
+                private static final Logger LOG = LoggerFactory.getLogger(FooService.class);
+                private static final int LIMIT;
+                @Qualifier("primary")
+                private final FooRepository repo;
+                private final Bar bar;
+                private final List<String> names = new ArrayList<>();
+
+                void run(final Baz baz) {
+                    final int count = 1;
+                    final Local local;
+                    String brace = "{";
+                }
+
+                private final Qux qux;
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                Parameter {
+                    annotations: vec!["@Qualifier(\"primary\")".to_string()],
+                    class: "FooRepository".to_string(),
+                    name: "repo".to_string(),
+                    collection: false,
+                    qualifier: Some(Qualifier::Name("primary".to_string())),
+                },
+                Parameter {
+                    annotations: vec![],
+                    class: "Bar".to_string(),
+                    name: "bar".to_string(),
+                    collection: false,
+                    qualifier: None,
+                },
+                Parameter {
+                    annotations: vec![],
+                    class: "Qux".to_string(),
+                    name: "qux".to_string(),
+                    collection: false,
+                    qualifier: None,
+                }
+            ],
+            class.parameters()
+        );
+    }
+
+    #[test]
+    fn parse_final_fields_deduplicates_constructor_parameters() {
+        let (_, class) = parse_class(
+            r#"
+            package a.b.c;
+
+            @Service
+            public class FooService {
+                private final Bar bar;
+                private final Baz baz;
+
+                FooService(Bar bar) {
+                    this.bar = bar;
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        let names: Vec<&str> = class.parameters().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(vec!["bar", "baz"], names);
+    }
+
+    #[test]
+    fn parse_constructor_ignores_calls_in_bodies() {
+        let body = r#"
+            public static Foo create(Qux qux) {
+                return new Foo(qux, null);
+            }
+
+            public Foo(@Qualifier("bar") Bar bar, Baz baz) {
+                super(baz);
+                this.bar = bar;
+            }
+
+            public Foo(Bar bar) {
+                this(bar, new Baz());
+            }
+        "#;
+        let params = parse_constructor("Foo", body).unwrap();
+        assert_eq!(
+            vec![
+                Parameter {
+                    annotations: vec!["@Qualifier(\"bar\")".to_string()],
+                    class: "Bar".to_string(),
+                    name: "bar".to_string(),
+                    collection: false,
+                    qualifier: Some(Qualifier::Name("bar".to_string())),
+                },
+                Parameter {
+                    annotations: vec![],
+                    class: "Baz".to_string(),
+                    name: "baz".to_string(),
+                    collection: false,
+                    qualifier: None,
+                }
+            ],
+            params
+        )
+    }
+
+    #[test]
+    pub fn parse_line_numbers_test() {
+        let source = normalize_source(
+            r#"package a.b.c;
+
+import a.b.Foo;
+
+/**
+ * Configures the foos.
+ */
+@Configuration
+@ComponentScan(
+    "a.b.c"
+)
+public class FooConfig {
+
+    @Autowired
+    private Foo foo;
+
+    @Primary
+    @Bean
+    public Bar bar(
+        Foo foo
+    ) {
+        return new Bar(foo);
+    }
+
+    // @Bean Commented out();
+    @Bean
+    Baz baz() { return new Baz(); }
+}
+"#,
+        );
+        let (_, class) = parse_class(&source).unwrap();
+        let beans: Vec<_> = class
+            .bean_defs()
+            .iter()
+            .map(|b| (b.name(), b.line()))
+            .collect();
+        assert_eq!(Some(12), class.line());
+        assert_eq!(Some(14), class.autowires()[0].line());
+        assert_eq!(vec![("bar", Some(17)), ("baz", Some(26))], beans);
+    }
+
+    #[test]
+    pub fn parse_groovy_configuration_test() {
+        let (_, class) = parse_class(
+            r#"
+            package com.example.config
+
+            import com.example.Foo
+
+            @Configuration
+            @ComponentScan("com.example")
+            class AppConfig {
+
+                @Autowired
+                Foo foo
+
+                @Autowired
+                def untyped
+
+                @Bean
+                Bar bar(Foo foo) {
+                    new Bar(foo)
+                }
+
+                @Bean
+                def baz() {
+                    return new Baz()
+                }
+
+                @Bean
+                def unknown() {
+                    createUnknown()
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("com.example.config", class.package());
+        assert_eq!("AppConfig", class.name());
+        assert_eq!(Some(&ComponentType::Configuration), class.component_type());
+        assert_eq!(&["com.example.Foo"], class.java_imports());
+        assert_eq!(&["com.example"], class.component_scans());
+        let autowires: Vec<_> = class.autowires().iter().map(|a| a.class()).collect();
+        assert_eq!(vec!["Foo"], autowires);
+        let beans: Vec<_> = class
+            .bean_defs()
+            .iter()
+            .map(|b| (b.name(), b.class(), b.parameters().len()))
+            .collect();
+        assert_eq!(vec![("bar", "Bar", 1), ("baz", "Baz", 0)], beans);
+    }
+
+    #[test]
+    fn parse_constructor_prefers_autowired() {
+        let body = r#"
+            public Foo(Bar bar) {
+                this(bar, new Baz());
+            }
+
+            @Autowired
+            public Foo(Bar bar, Baz baz) {
+                this.bar = bar;
+            }
+        "#;
+        let params = parse_constructor("Foo", body).unwrap();
+        let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(vec!["bar", "baz"], names);
+    }
+
+    #[test]
+    fn parse_constructor_with_paren_on_next_line() {
+        let body = normalize_source(
+            r#"
+            // Use Foo(Baz baz) for tests
+            /* Foo(Qux qux) */
+            private final Bar bar;
+
+            public Foo
+                (Bar bar) {
+                this.bar = bar;
+            }
+        "#,
+        );
+        let params = parse_constructor("Foo", &body).unwrap();
+        assert_eq!(
+            vec![Parameter {
+                annotations: vec![],
+                class: "Bar".to_string(),
+                name: "bar".to_string(),
+                collection: false,
+                qualifier: None,
+            }],
+            params
+        );
+    }
+
     #[test]
     fn parse_constructor_works() {
         let body = r#"
@@ -322,11 +2290,15 @@ mod tests {
                     annotations: vec!["@NotNull".to_string(), "@Something".to_string()],
                     class: "Bar".to_string(),
                     name: "bar".to_string(),
+                    collection: false,
+                    qualifier: None,
                 },
                 Parameter {
                     annotations: vec![],
                     class: "Baz".to_string(),
                     name: "baz".to_string(),
+                    collection: false,
+                    qualifier: None,
                 }
             ],
             params
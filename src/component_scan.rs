@@ -1 +1,210 @@
+//! Resolution of the classes picked up by `@ComponentScan`.
 
+use regex::Regex;
+use strum::EnumString;
+
+use crate::{
+    annotation::{Annotation, AnnotationArg},
+    class::Class,
+    component_type::ComponentType,
+};
+
+/// The kinds of filters of `@ComponentScan.Filter`, named as in Spring's `FilterType`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, EnumString)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum FilterType {
+    #[default]
+    Annotation,
+    AssignableType,
+    Aspectj,
+    Regex,
+    Custom,
+}
+
+/// A filter of `includeFilters` or `excludeFilters` on a `@ComponentScan`.
+#[derive(Debug, Clone)]
+pub struct ScanFilter {
+    filter_type: FilterType,
+    classes: Vec<String>,
+    patterns: Vec<String>,
+    /// The patterns compiled once, leaving out the invalid ones.
+    regexes: Vec<Regex>,
+}
+
+impl PartialEq for ScanFilter {
+    fn eq(&self, other: &Self) -> bool {
+        // The regexes are compiled from the patterns
+        self.filter_type == other.filter_type
+            && self.classes == other.classes
+            && self.patterns == other.patterns
+    }
+}
+
+impl Eq for ScanFilter {}
+
+impl ScanFilter {
+    pub fn new(filter_type: FilterType, classes: Vec<String>, patterns: Vec<String>) -> Self {
+        let regexes = patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(&format!("^(?:{})$", pattern)) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    tracing::warn!("Invalid component scan pattern {}: {}", pattern, e);
+                    None
+                }
+            })
+            .collect();
+        ScanFilter {
+            filter_type,
+            classes,
+            patterns,
+            regexes,
+        }
+    }
+
+    /// Reads a filter from a `@ComponentScan.Filter` annotation.
+    pub fn from_annotation(annotation: &Annotation) -> Self {
+        let filter_type = annotation
+            .get("type")
+            .and_then(|arg| match arg {
+                AnnotationArg::Enum(r) | AnnotationArg::Reference(r) => r.rsplit('.').next(),
+                _ => None,
+            })
+            .and_then(|t| t.parse().ok())
+            .unwrap_or_default();
+        let values = |arg: Option<&AnnotationArg>| {
+            arg.and_then(AnnotationArg::as_array)
+                .unwrap_or(arg.map(std::slice::from_ref).unwrap_or_default())
+                .to_vec()
+        };
+        let mut classes = values(annotation.get("classes"));
+        classes.extend(values(annotation.value()));
+        ScanFilter::new(
+            filter_type,
+            classes
+                .iter()
+                .filter_map(AnnotationArg::as_class)
+                .map(String::from)
+                .collect(),
+            values(annotation.get("pattern"))
+                .iter()
+                .filter_map(AnnotationArg::as_string)
+                .map(unescape)
+                .collect(),
+        )
+    }
+
+    pub fn filter_type(&self) -> &FilterType {
+        &self.filter_type
+    }
+
+    pub fn classes(&self) -> &[String] {
+        self.classes.as_ref()
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        self.patterns.as_ref()
+    }
+
+    /// Whether the filter applies to `class`. AspectJ and custom filters
+    /// cannot be evaluated from source, so they never match.
+    pub fn matches(&self, class: &Class) -> bool {
+        let simple_names = self
+            .classes
+            .iter()
+            .map(|c| c.rsplit('.').next().unwrap_or(c))
+            .collect::<Vec<_>>();
+        match self.filter_type {
+            FilterType::Annotation => class.component_type().is_some_and(|t| {
+                simple_names.iter().any(|name| {
+                    ComponentType::from_annotation_name(name).is_some_and(|s| t.is_a(&s))
+                })
+            }),
+            FilterType::AssignableType => simple_names
+                .iter()
+                .any(|name| class.name() == *name || class.interfaces().iter().any(|i| i == name)),
+            FilterType::Regex => {
+                let name = class.qualified_name();
+                self.regexes.iter().any(|regex| regex.is_match(&name))
+            }
+            FilterType::Aspectj | FilterType::Custom => false,
+        }
+    }
+}
+
+/// The value of a Java string literal, whose backslashes and quotes are escaped.
+fn unescape(literal: &str) -> String {
+    let mut value = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.extend(chars.next()),
+            c => value.push(c),
+        }
+    }
+    value
+}
+
+/// Whether a class in package `package` is picked up by a component scan of `scan`.
+pub fn package_in_scan(package: &str, scan: &str) -> bool {
+    package == scan || package.starts_with(&format!("{}.", scan))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{class::ClassBuilder, component_type::ComponentType};
+
+    use super::{package_in_scan, unescape, FilterType, ScanFilter};
+
+    #[test]
+    fn scan_filter_matches() {
+        let class = ClassBuilder::default()
+            .package("a.b.test".to_string())
+            .name("FooController".to_string())
+            .component_type(Some(ComponentType::RestController))
+            .interfaces(vec!["IFoo".to_string()])
+            .build()
+            .unwrap();
+        let filter = |filter_type, classes: &[&str], patterns: &[&str]| {
+            ScanFilter::new(
+                filter_type,
+                classes.iter().map(|c| c.to_string()).collect(),
+                patterns.iter().map(|p| p.to_string()).collect(),
+            )
+        };
+        assert!(filter(FilterType::Annotation, &["RestController"], &[]).matches(&class));
+        assert!(filter(FilterType::Annotation, &["Controller"], &[]).matches(&class));
+        assert!(!filter(FilterType::Annotation, &["Service"], &[]).matches(&class));
+        assert!(filter(FilterType::AssignableType, &["a.b.IFoo"], &[]).matches(&class));
+        assert!(filter(FilterType::Regex, &[], &["a\\.b\\.test\\..*"]).matches(&class));
+        assert!(!filter(FilterType::Regex, &[], &["a\\.b"]).matches(&class));
+        // Invalid patterns are left out
+        assert!(filter(FilterType::Regex, &[], &["(", "a\\..*"]).matches(&class));
+    }
+
+    #[test]
+    fn unescape_java_literal() {
+        assert_eq!("a\\.b", unescape("a\\\\.b"));
+        assert_eq!("say \"hi\"", unescape("say \\\"hi\\\""));
+    }
+
+    #[test]
+    fn package_in_scan_exact_match() {
+        assert!(package_in_scan("a.b", "a.b"));
+    }
+
+    #[test]
+    fn package_in_scan_subpackage() {
+        assert!(package_in_scan("a.b.c", "a.b"));
+    }
+
+    #[test]
+    fn package_in_scan_rejects_sibling_with_common_prefix() {
+        assert!(!package_in_scan("a.bc", "a.b"));
+    }
+
+    #[test]
+    fn package_in_scan_rejects_parent() {
+        assert!(!package_in_scan("a", "a.b"));
+    }
+}
@@ -1,24 +1,136 @@
-use strum::EnumIter;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString};
 
-#[derive(Debug, Clone, PartialEq, Eq, EnumIter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EnumIter, EnumString, Serialize, Deserialize)]
+#[strum(ascii_case_insensitive)]
 pub enum ComponentType {
     SpringBootApplication,
     Configuration,
     Controller,
+    /// `@RestController`, a controller returning response bodies rather than views.
+    RestController,
     Service,
     Repository,
     Component,
+    FeignClient,
+    /// MyBatis `@Mapper` interface.
+    Mapper,
+    /// Spring Boot `@TestConfiguration`, only found when test sources are included.
+    TestConfiguration,
 }
 
 impl ComponentType {
+    /// The component type of a stereotype annotation, given its simple name without the `@`.
+    pub fn from_annotation_name(name: &str) -> Option<ComponentType> {
+        match name {
+            "SpringBootApplication" => Some(ComponentType::SpringBootApplication),
+            "Configuration" => Some(ComponentType::Configuration),
+            "Controller" => Some(ComponentType::Controller),
+            "RestController" => Some(ComponentType::RestController),
+            "Service" => Some(ComponentType::Service),
+            "Repository" => Some(ComponentType::Repository),
+            "Component" => Some(ComponentType::Component),
+            "FeignClient" => Some(ComponentType::FeignClient),
+            "Mapper" => Some(ComponentType::Mapper),
+            "TestConfiguration" => Some(ComponentType::TestConfiguration),
+            _ => None,
+        }
+    }
+
+    /// Whether a component of this type is also one of the other type, as a `@RestController`
+    /// is meta-annotated with `@Controller`.
+    pub fn is_a(&self, other: &ComponentType) -> bool {
+        self == other
+            || matches!(
+                (self, other),
+                (ComponentType::RestController, ComponentType::Controller)
+            )
+    }
+
+    /// Plural name used for files holding components of this type.
+    pub fn plural(&self) -> &'static str {
+        match self {
+            ComponentType::SpringBootApplication => "springbootapplications",
+            ComponentType::Configuration => "configurations",
+            ComponentType::Controller => "controllers",
+            ComponentType::RestController => "restcontrollers",
+            ComponentType::Service => "services",
+            ComponentType::Repository => "repositories",
+            ComponentType::Component => "components",
+            ComponentType::FeignClient => "feignclients",
+            ComponentType::Mapper => "mappers",
+            ComponentType::TestConfiguration => "testconfigurations",
+        }
+    }
+
+    /// Graphviz node shape, so that types can be told apart without color.
+    pub fn shape(&self) -> &'static str {
+        match self {
+            ComponentType::SpringBootApplication => "house",
+            ComponentType::Configuration => "component",
+            ComponentType::Controller => "hexagon",
+            ComponentType::RestController => "octagon",
+            ComponentType::Service => "box",
+            ComponentType::Repository => "cylinder",
+            ComponentType::Component => "ellipse",
+            ComponentType::FeignClient => "cds",
+            ComponentType::Mapper => "tab",
+            ComponentType::TestConfiguration => "note",
+        }
+    }
+
     pub fn color_code(&self) -> &'static str {
         match self {
             ComponentType::SpringBootApplication => "#2c9162",
             ComponentType::Configuration => "#28a9e0",
             ComponentType::Controller => "#7050bf",
+            ComponentType::RestController => "#a58be0",
             ComponentType::Service => "#a81347",
             ComponentType::Repository => "#e06907",
             ComponentType::Component => "#ffc400",
+            ComponentType::FeignClient => "#5c8a3a",
+            ComponentType::Mapper => "#8c564b",
+            ComponentType::TestConfiguration => "#7f7f7f",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ComponentType;
+
+    #[test]
+    fn from_annotation_name() {
+        for (name, component_type) in [
+            (
+                "SpringBootApplication",
+                ComponentType::SpringBootApplication,
+            ),
+            ("Configuration", ComponentType::Configuration),
+            ("Controller", ComponentType::Controller),
+            ("RestController", ComponentType::RestController),
+            ("Service", ComponentType::Service),
+            ("Repository", ComponentType::Repository),
+            ("Component", ComponentType::Component),
+            ("FeignClient", ComponentType::FeignClient),
+            ("Mapper", ComponentType::Mapper),
+            ("TestConfiguration", ComponentType::TestConfiguration),
+        ] {
+            assert_eq!(
+                Some(component_type),
+                ComponentType::from_annotation_name(name)
+            );
         }
+        assert_eq!(None, ComponentType::from_annotation_name("Autowired"));
+        assert_eq!(None, ComponentType::from_annotation_name("@Service"));
+        assert_eq!(None, ComponentType::from_annotation_name("service"));
+    }
+
+    #[test]
+    fn rest_controller_is_a_controller() {
+        assert!(ComponentType::RestController.is_a(&ComponentType::Controller));
+        assert!(ComponentType::RestController.is_a(&ComponentType::RestController));
+        assert!(!ComponentType::Controller.is_a(&ComponentType::RestController));
+        assert!(!ComponentType::Service.is_a(&ComponentType::Controller));
     }
 }
@@ -0,0 +1,187 @@
+use clap::Parser;
+use itertools::Itertools;
+use std::{fmt::Display, str::FromStr};
+use strum::{EnumIter, IntoEnumIterator};
+
+#[derive(Debug, Clone, PartialEq, Eq, Parser, EnumIter)]
+pub enum Feature {
+    Import,
+    ComponentScan,
+    Autowired,
+    Bean,
+    ConstructorInjection,
+    Entity,
+    Value,
+    CombineImplAndInterface,
+}
+
+impl Feature {
+    /// A one-line description of what the feature draws.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Feature::Import => "Edges from configurations to the classes they import",
+            Feature::ComponentScan => "Scanned packages and the components found in them",
+            Feature::Autowired => "Edges to dependencies injected into fields",
+            Feature::Bean => "Beans defined by configuration classes",
+            Feature::ConstructorInjection => "Edges to dependencies injected through constructors",
+            Feature::Entity => "Edges from Spring Data repositories to their entities",
+            Feature::Value => "Configuration properties injected with @Value",
+            Feature::CombineImplAndInterface => "Draw implementations as their first interface",
+        }
+    }
+
+    /// The annotations the feature is based on.
+    pub fn annotations(&self) -> &'static [&'static str] {
+        match self {
+            Feature::Import => &["@Import"],
+            Feature::ComponentScan => &["@ComponentScan", "@SpringBootApplication"],
+            Feature::Autowired => &["@Autowired", "@Inject"],
+            Feature::Bean => &["@Bean"],
+            Feature::ConstructorInjection => &[],
+            Feature::Entity => &[],
+            Feature::Value => &["@Value"],
+            Feature::CombineImplAndInterface => &[],
+        }
+    }
+}
+
+impl Display for Feature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let debug = format!("{:?}", self);
+        write!(f, "{}", debug.to_lowercase())
+    }
+}
+
+impl FromStr for Feature {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        for feature in Feature::iter() {
+            if input == feature.to_string() {
+                return Ok(feature);
+            }
+        }
+        Err(format!("unknown feature {}", input))
+    }
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct Features {
+    features: Vec<Feature>,
+}
+
+impl Features {
+    pub fn from_vec(features: Vec<Feature>) -> Self {
+        Self { features }
+    }
+
+    pub fn contains(&self, feature: &Feature) -> bool {
+        self.features.contains(feature)
+    }
+
+    /// The enabled features, in the order they were given.
+    pub fn iter(&self) -> impl Iterator<Item = &Feature> {
+        self.features.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self {
+            features: Feature::iter().collect(),
+        }
+    }
+}
+
+impl FromIterator<Feature> for Features {
+    fn from_iter<T: IntoIterator<Item = Feature>>(iter: T) -> Self {
+        Self {
+            features: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Display for Features {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let comma_separated_features = self.features.iter().map(|f| f.to_string()).join(",");
+        write!(f, "{}", comma_separated_features)
+    }
+}
+
+impl FromStr for Features {
+    type Err = String;
+
+    /// Parses a comma separated list of features, where `all` adds every feature,
+    /// `none` removes every feature and `-feature` removes a single one.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut features = Vec::new();
+        for token in s.split(',').map(|s| s.trim()) {
+            match token {
+                "all" => features = Feature::iter().collect(),
+                "none" => features.clear(),
+                _ => {
+                    if let Some(removed) = token.strip_prefix('-') {
+                        let removed = Feature::from_str(removed)?;
+                        features.retain(|f| f != &removed);
+                    } else {
+                        let feature = Feature::from_str(token)?;
+                        if !features.contains(&feature) {
+                            features.push(feature);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Self { features })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::{Feature, Features};
+
+    #[test]
+    fn parse_all_features() {
+        let features: Features = "all".parse().unwrap();
+        assert!(Feature::iter().all(|f| features.contains(&f)));
+    }
+
+    #[test]
+    fn parse_no_features() {
+        let features: Features = "none".parse().unwrap();
+        assert!(Feature::iter().all(|f| !features.contains(&f)));
+    }
+
+    #[test]
+    fn parse_all_except_one_feature() {
+        let features: Features = "all,-bean".parse().unwrap();
+        assert!(!features.contains(&Feature::Bean));
+        assert!(Feature::iter()
+            .filter(|f| f != &Feature::Bean)
+            .all(|f| features.contains(&f)));
+    }
+
+    #[test]
+    fn features_accessors() {
+        let features = Features::from_vec(vec![Feature::Bean, Feature::Autowired]);
+        assert_eq!(
+            vec![&Feature::Bean, &Feature::Autowired],
+            features.iter().collect::<Vec<_>>()
+        );
+        assert!(!features.is_empty());
+        assert!(Features::from_vec(vec![]).is_empty());
+        assert!("none".parse::<Features>().unwrap().is_empty());
+        assert_eq!(Feature::iter().count(), Features::default().iter().count());
+    }
+
+    #[test]
+    fn parse_unknown_feature_fails() {
+        assert!("all,-foo".parse::<Features>().is_err());
+    }
+}
@@ -0,0 +1,20 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeignClient {
+    name: String,
+    url: Option<String>,
+}
+
+impl FeignClient {
+    pub fn new(name: String, url: Option<String>) -> Self {
+        FeignClient { name, url }
+    }
+
+    /// The name of the remote service.
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+}
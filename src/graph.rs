@@ -0,0 +1,1431 @@
+//! Dependency graph between parsed classes.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator};
+
+use crate::{
+    autowired::Qualifier,
+    bean::Bean,
+    class::Class,
+    component_scan::package_in_scan,
+    component_type::ComponentType,
+    feature::{Feature, Features},
+};
+
+/// What an edge between two nodes stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, EnumString, Serialize, Deserialize)]
+#[strum(ascii_case_insensitive)]
+pub enum EdgeKind {
+    /// A configuration importing another with `@Import`.
+    Import,
+    /// A class scanning a package with `@ComponentScan`.
+    ComponentScan,
+    /// A scanned package containing a component.
+    Contains,
+    /// A dependency injected into a field.
+    Autowired,
+    /// A dependency injected through a constructor.
+    ConstructorInjection,
+    /// A dependency injected into a `@Bean` method.
+    BeanInjection,
+    /// A configuration defining a bean.
+    Bean,
+    /// An interface implemented by a class.
+    Implements,
+    /// A property injected with `@Value`.
+    Value,
+    /// A Spring Data repository managing an entity.
+    Entity,
+    /// A remote service called through a `@FeignClient`.
+    FeignClient,
+}
+
+impl EdgeKind {
+    /// Text drawn on edges of this kind.
+    pub fn label(&self) -> &'static str {
+        match self {
+            EdgeKind::Import => "@Import",
+            EdgeKind::ComponentScan => "@ComponentScan",
+            EdgeKind::Contains => "contains",
+            EdgeKind::Autowired => "@Autowired",
+            EdgeKind::ConstructorInjection => "Constructor",
+            EdgeKind::BeanInjection => "@Autowired (CI)",
+            EdgeKind::Bean => "@Bean",
+            EdgeKind::Implements => "impl",
+            EdgeKind::Value => "@Value",
+            EdgeKind::Entity => "entity",
+            EdgeKind::FeignClient => "@FeignClient",
+        }
+    }
+
+    /// Whether the edge is a dependency injected into a class or bean.
+    pub fn is_injection(&self) -> bool {
+        matches!(
+            self,
+            EdgeKind::Autowired | EdgeKind::ConstructorInjection | EdgeKind::BeanInjection
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Edge {
+    from: String,
+    to: String,
+    kind: EdgeKind,
+    label: String,
+    ambiguous: bool,
+    /// The injected field or parameter name.
+    #[serde(default)]
+    name: Option<String>,
+    /// Whether the injection may be left out, as with `@Autowired(required = false)`.
+    #[serde(default)]
+    optional: bool,
+    /// Whether every bean of the type is injected as a collection.
+    #[serde(default)]
+    collection: bool,
+}
+
+impl Edge {
+    pub fn new(from: String, to: String, kind: EdgeKind) -> Self {
+        Edge {
+            from,
+            to,
+            kind,
+            label: kind.label().to_string(),
+            ambiguous: false,
+            name: None,
+            optional: false,
+            collection: false,
+        }
+    }
+
+    /// Replaces the label of the kind, such as to add the injected name.
+    pub fn with_label(self, label: String) -> Self {
+        Edge { label, ..self }
+    }
+
+    /// Marks the edge as one of several candidates for an injection point.
+    pub fn with_ambiguous(self, ambiguous: bool) -> Self {
+        Edge { ambiguous, ..self }
+    }
+
+    pub fn from(&self) -> &str {
+        self.from.as_ref()
+    }
+
+    pub fn to(&self) -> &str {
+        self.to.as_ref()
+    }
+
+    pub fn kind(&self) -> EdgeKind {
+        self.kind
+    }
+
+    pub fn label(&self) -> &str {
+        self.label.as_ref()
+    }
+
+    pub fn ambiguous(&self) -> bool {
+        self.ambiguous
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn optional(&self) -> bool {
+        self.optional
+    }
+
+    pub fn collection(&self) -> bool {
+        self.collection
+    }
+
+    /// What tells edges apart, the nodes they join and their kind.
+    pub fn key(&self) -> (&str, &str, EdgeKind) {
+        (&self.from, &self.to, self.kind)
+    }
+}
+
+/// Collapses the edges between the same pair of nodes into the first of them, labelled
+/// with each distinct label followed by how many times it occurred, as in
+/// `@Autowired ×2, @Autowired (CI)`. The merged edge is ambiguous if any of them is.
+pub fn merge_parallel_edges(edges: impl IntoIterator<Item = Edge>) -> Vec<Edge> {
+    let mut merged: Vec<(Edge, Vec<(String, usize)>)> = Vec::new();
+    // Position in `merged` of the edge between each pair of nodes
+    let mut positions: HashMap<(String, String), usize> = HashMap::new();
+    for edge in edges {
+        let pair = (edge.from.clone(), edge.to.clone());
+        match positions.get(&pair) {
+            Some(&i) => {
+                let (first, labels) = &mut merged[i];
+                first.ambiguous |= edge.ambiguous;
+                match labels.iter_mut().find(|(label, _)| *label == edge.label) {
+                    Some((_, count)) => *count += 1,
+                    None => labels.push((edge.label, 1)),
+                }
+            }
+            None => {
+                positions.insert(pair, merged.len());
+                let labels = vec![(edge.label.clone(), 1)];
+                merged.push((edge, labels));
+            }
+        }
+    }
+    merged
+        .into_iter()
+        .map(|(edge, labels)| {
+            let label = labels
+                .iter()
+                .map(|(label, count)| match count {
+                    1 => label.clone(),
+                    n => format!("{} ×{}", label, n),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            edge.with_label(label)
+        })
+        .collect()
+}
+
+/// The beans an injection point resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Injection {
+    pub targets: Vec<String>,
+    /// Whether Spring could not choose between the targets.
+    pub ambiguous: bool,
+}
+
+/// The bean name of a class, given by its stereotype annotation or else its simple name
+/// starting in lowercase.
+fn bean_name(class: &Class) -> String {
+    if let Some(name) = class.bean_name() {
+        return name.to_string();
+    }
+    let mut chars = class.name().chars();
+    chars
+        .next()
+        .map(|c| c.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// The simple name of a possibly qualified type, such as `Foo` for `a.b.Foo`.
+fn simple_name(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// The qualified name of a type referenced from `from`: as written when qualified, else
+/// the import of `from` naming it, else in the package of `from`.
+fn qualify(from: &Class, name: &str) -> String {
+    if name.contains('.') {
+        return name.to_string();
+    }
+    from.java_imports()
+        .iter()
+        .find(|i| simple_name(i) == name)
+        .cloned()
+        .unwrap_or_else(|| format!("{}.{}", from.package(), name))
+}
+
+/// A node of the graph, drawn for a parsed class.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Node {
+    id: String,
+    label: String,
+    component_type: Option<ComponentType>,
+    color: Option<String>,
+}
+
+impl Node {
+    pub fn id(&self) -> &str {
+        self.id.as_ref()
+    }
+
+    pub fn label(&self) -> &str {
+        self.label.as_ref()
+    }
+
+    pub fn component_type(&self) -> Option<&ComponentType> {
+        self.component_type.as_ref()
+    }
+
+    /// Fill color of the node, if it is a component.
+    pub fn color(&self) -> Option<&str> {
+        self.color.as_deref()
+    }
+}
+
+impl From<&Class> for Node {
+    /// Creates a node identified by the fully qualified name of the class and labelled with
+    /// its simple name. Graphs identify classes by [`NodeIds::node`] instead, which only
+    /// qualifies the names that several classes share.
+    fn from(class: &Class) -> Self {
+        Node {
+            id: class.qualified_name(),
+            label: class.name().to_string(),
+            component_type: class.component_type().cloned(),
+            color: class.component_type().map(|t| t.color_code().to_string()),
+        }
+    }
+}
+
+/// Identifies the nodes of parsed classes. A class is identified by its simple name,
+/// unless several classes share it, in which case its fully qualified name is used.
+#[derive(Debug, Clone)]
+pub struct NodeIds<'a> {
+    /// Parsed classes keyed by simple name.
+    by_name: HashMap<&'a str, Vec<&'a Class>>,
+    /// Parsed classes keyed by qualified name.
+    by_qualified_name: HashMap<String, &'a Class>,
+    ambiguous: HashSet<&'a str>,
+    /// Classes implementing each interface, keyed by the node id of the interface.
+    implementations: HashMap<String, Vec<&'a Class>>,
+    /// Node ids of the types produced by `@Bean` methods, keyed by their qualified name.
+    beans: HashMap<String, String>,
+    /// The same node ids keyed by simple name, leaving out names shared by several beans.
+    beans_by_simple_name: HashMap<&'a str, String>,
+    /// Node ids of the types produced by `@Bean` methods, keyed by the bean name.
+    bean_methods: HashMap<&'a str, Vec<String>>,
+    /// Node ids of the types produced by `@Primary` `@Bean` methods.
+    primary: HashSet<String>,
+}
+
+impl<'a> NodeIds<'a> {
+    pub fn new(classes: &'a [Class]) -> Self {
+        let mut seen = HashSet::new();
+        let ambiguous = classes
+            .iter()
+            .map(|c| c.name())
+            .filter(|name| !seen.insert(*name))
+            .collect();
+        let mut by_name: HashMap<&str, Vec<&Class>> = HashMap::new();
+        let mut by_qualified_name = HashMap::new();
+        for class in classes {
+            by_name.entry(class.name()).or_default().push(class);
+            by_qualified_name
+                .entry(class.qualified_name())
+                .or_insert(class);
+        }
+        let mut ids = NodeIds {
+            by_name,
+            by_qualified_name,
+            ambiguous,
+            implementations: HashMap::new(),
+            beans: HashMap::new(),
+            beans_by_simple_name: HashMap::new(),
+            bean_methods: HashMap::new(),
+            primary: HashSet::new(),
+        };
+        let mut beans = HashMap::new();
+        let mut by_simple_name: HashMap<&str, Option<String>> = HashMap::new();
+        let mut bean_methods: HashMap<&str, Vec<String>> = HashMap::new();
+        let mut primary = HashSet::new();
+        for class in classes {
+            for bean in class.bean_defs() {
+                let id = ids.resolve(class, bean.class());
+                // The bean picks the parsed class it constructs as well
+                let produced: Vec<String> = std::iter::once(id.clone())
+                    .chain(bean.implementation().map(|i| ids.resolve(class, i)))
+                    .collect();
+                if bean.primary() {
+                    primary.extend(produced.iter().cloned());
+                }
+                bean_methods
+                    .entry(bean.name())
+                    .or_default()
+                    .extend(produced);
+                // Injections of the inferred implementation get the bean as well
+                for name in std::iter::once(bean.class()).chain(bean.implementation()) {
+                    beans
+                        .entry(qualify(class, name))
+                        .or_insert_with(|| id.clone());
+                    by_simple_name
+                        .entry(simple_name(name))
+                        .and_modify(|other| {
+                            if other.as_ref() != Some(&id) {
+                                *other = None;
+                            }
+                        })
+                        .or_insert_with(|| Some(id.clone()));
+                }
+            }
+        }
+        ids.beans = beans;
+        ids.bean_methods = bean_methods;
+        ids.primary = primary;
+        ids.beans_by_simple_name = by_simple_name
+            .into_iter()
+            .filter_map(|(name, id)| Some((name, id?)))
+            .collect();
+        for class in classes {
+            for interface in class.interfaces() {
+                let interface = ids.resolve(class, interface);
+                ids.implementations
+                    .entry(interface)
+                    .or_default()
+                    .push(class);
+            }
+        }
+        ids
+    }
+
+    /// The node id of a parsed class.
+    pub fn id(&self, class: &Class) -> String {
+        if self.ambiguous.contains(class.name()) {
+            class.qualified_name()
+        } else {
+            class.name().to_string()
+        }
+    }
+
+    /// The node of a parsed class, identified by its node id rather than its qualified name.
+    pub fn node(&self, class: &Class) -> Node {
+        Node {
+            id: self.id(class),
+            ..Node::from(class)
+        }
+    }
+
+    /// The node id a class is drawn as, which is that of its first interface when
+    /// implementations are combined with their interfaces.
+    pub fn drawn_id(&self, class: &Class, features: &Features) -> String {
+        match class.interfaces().first() {
+            Some(interface) if features.contains(&Feature::CombineImplAndInterface) => {
+                self.resolve(class, interface)
+            }
+            _ => self.id(class),
+        }
+    }
+
+    /// Resolves a type referenced from `from` to a node id. When several classes
+    /// share the name, the one imported by `from` or in its package is preferred.
+    /// Types that are not parsed classes resolve to the `@Bean` method producing them.
+    pub fn resolve(&self, from: &Class, name: &str) -> String {
+        let candidates: Vec<&Class> = if name.contains('.') {
+            self.by_qualified_name
+                .get(name)
+                .into_iter()
+                .copied()
+                .collect()
+        } else {
+            self.by_name.get(name).cloned().unwrap_or_default()
+        };
+        let resolved = candidates
+            .iter()
+            .find(|c| from.java_imports().contains(&c.qualified_name()))
+            .or_else(|| candidates.iter().find(|c| c.package() == from.package()))
+            .or_else(|| candidates.first());
+        match resolved {
+            Some(class) => self.id(class),
+            None => self
+                .beans
+                .get(&qualify(from, name))
+                .or_else(|| self.beans_by_simple_name.get(simple_name(name)))
+                .cloned()
+                .unwrap_or_else(|| name.to_string()),
+        }
+    }
+
+    /// Finds the parsed class an `@Import` refers to, by simple or fully qualified name.
+    pub fn resolve_import(&self, import: &str) -> Option<&'a Class> {
+        if import.contains('.') {
+            self.by_qualified_name.get(import).copied()
+        } else {
+            self.by_name.get(import)?.first().copied()
+        }
+    }
+
+    /// Whether a class is the bean picked by `qualifier`: its bean name, the name of a
+    /// `@Bean` method producing it, or a custom qualifier annotation such as `@PrimaryDb`.
+    fn is_qualified(&self, class: &Class, qualifier: &Qualifier) -> bool {
+        match qualifier {
+            Qualifier::Annotation(annotation) => {
+                class.annotations().iter().any(|a| a == annotation)
+            }
+            Qualifier::Name(name) => {
+                bean_name(class) == *name
+                    || self
+                        .bean_methods
+                        .get(name.as_str())
+                        .is_some_and(|ids| ids.contains(&self.id(class)))
+            }
+        }
+    }
+
+    /// Whether a class is marked `@Primary`, or produced by a `@Primary` `@Bean` method.
+    fn is_primary(&self, class: &Class) -> bool {
+        class.annotations().iter().any(|a| a == "Primary") || self.primary.contains(&self.id(class))
+    }
+
+    /// Resolves an injected type referenced from `from` to the beans Spring would inject:
+    /// the implementations of an interface, or else the type itself. Several implementations
+    /// are ambiguous, unless a collection takes all of them, or the qualifier or `@Primary`
+    /// picks one.
+    pub fn resolve_injection(
+        &self,
+        from: &Class,
+        name: &str,
+        qualifier: Option<&Qualifier>,
+        collection: bool,
+    ) -> Injection {
+        let target = self.resolve(from, name);
+        let implementations = match self.implementations.get(&target) {
+            Some(implementations) => implementations,
+            None => {
+                return Injection {
+                    targets: vec![target],
+                    ambiguous: false,
+                }
+            }
+        };
+        let chosen = if collection {
+            None
+        } else {
+            qualifier
+                .and_then(|q| implementations.iter().find(|c| self.is_qualified(c, q)))
+                .or_else(|| implementations.iter().find(|c| self.is_primary(c)))
+        };
+        match chosen {
+            Some(class) => Injection {
+                targets: vec![self.id(class)],
+                ambiguous: false,
+            },
+            None => {
+                // Collections are injected in order, unordered beans last
+                let mut implementations = implementations.clone();
+                implementations.sort_by_key(|c| c.order().unwrap_or(i32::MAX.into()));
+                Injection {
+                    targets: implementations.iter().map(|c| self.id(c)).collect(),
+                    ambiguous: !collection && implementations.len() > 1,
+                }
+            }
+        }
+    }
+}
+
+/// The nodes and edges of a graph that are not in an earlier one, and the other way around.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<Node>,
+    pub removed_nodes: Vec<Node>,
+    pub added_edges: Vec<Edge>,
+    pub removed_edges: Vec<Edge>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Graph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    interfaces: BTreeMap<String, Vec<String>>,
+}
+
+impl Graph {
+    /// Builds the edges of every feature between classes, keeping implementations
+    /// apart from their interfaces.
+    pub fn new(classes: &[Class]) -> Self {
+        let features = Feature::iter()
+            .filter(|f| *f != Feature::CombineImplAndInterface)
+            .collect();
+        Self::with_features(classes, &features)
+    }
+
+    /// Builds the edges of the enabled features between classes.
+    pub fn with_features(classes: &[Class], features: &Features) -> Self {
+        let ids = NodeIds::new(classes);
+        let combine = features.contains(&Feature::CombineImplAndInterface);
+        let feign_clients: HashMap<String, &str> = classes
+            .iter()
+            .filter_map(|c| c.feign_client().map(|f| (ids.id(c), f.name())))
+            .collect();
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut edges = Vec::new();
+        // The same injection may be found twice, so injection edges are kept once per key
+        let mut keys = HashSet::new();
+        let mut interfaces = BTreeMap::new();
+        for class in classes {
+            let id = ids.drawn_id(class, features);
+            // Implementations combined with their first interface are drawn as it
+            let node = Node {
+                id: id.clone(),
+                label: simple_name(&id).to_string(),
+                ..ids.node(class)
+            };
+            match nodes.iter_mut().find(|n| n.id == id) {
+                Some(existing) if existing.component_type.is_none() => *existing = node,
+                Some(_) => {}
+                None => nodes.push(node),
+            }
+            // Injected nodes, some of which may be feign clients
+            let mut injected = Vec::new();
+            for relation in class.relations(features) {
+                let kind = relation.kind;
+                match kind {
+                    EdgeKind::Import => {
+                        let import = ids
+                            .resolve_import(&relation.target)
+                            .map(|c| ids.id(c))
+                            .unwrap_or(relation.target);
+                        edges.push(Edge::new(id.clone(), import, kind));
+                    }
+                    EdgeKind::ComponentScan => {
+                        let package = relation.target;
+                        edges.push(Edge::new(id.clone(), package.clone(), kind));
+                        let scanned = classes.iter().filter(|c| {
+                            package_in_scan(c.package(), &package)
+                                && c.component_type().is_some()
+                                && !class.exclude_filters().iter().any(|f| f.matches(c))
+                        });
+                        for c in scanned {
+                            edges.push(Edge::new(package.clone(), ids.id(c), EdgeKind::Contains));
+                        }
+                    }
+                    EdgeKind::Implements => {
+                        let interface = ids.resolve(class, &relation.target);
+                        edges.push(Edge::new(interface, id.clone(), kind));
+                    }
+                    EdgeKind::Autowired
+                    | EdgeKind::ConstructorInjection
+                    | EdgeKind::BeanInjection => {
+                        let from = match &relation.bean {
+                            Some(bean) => ids.resolve(class, bean),
+                            None => id.clone(),
+                        };
+                        let injection = if combine {
+                            Injection {
+                                targets: vec![ids.resolve(class, &relation.target)],
+                                ambiguous: false,
+                            }
+                        } else {
+                            ids.resolve_injection(
+                                class,
+                                &relation.target,
+                                relation.qualifier.as_ref(),
+                                relation.collection,
+                            )
+                        };
+                        for target in injection.targets {
+                            if !keys.insert((from.clone(), target.clone(), kind)) {
+                                continue;
+                            }
+                            if relation.bean.is_none() {
+                                injected.push(target.clone());
+                            }
+                            edges.push(Edge {
+                                name: relation.name.clone(),
+                                optional: relation.optional,
+                                collection: relation.collection,
+                                ..Edge::new(from.clone(), target, kind)
+                                    .with_ambiguous(injection.ambiguous)
+                            });
+                        }
+                    }
+                    EdgeKind::Entity | EdgeKind::Bean => {
+                        let target = ids.resolve(class, &relation.target);
+                        edges.push(Edge::new(id.clone(), target, kind));
+                    }
+                    EdgeKind::Value => edges.push(Edge::new(id.clone(), relation.target, kind)),
+                    // Follow from the scans and injections above
+                    EdgeKind::Contains | EdgeKind::FeignClient => {}
+                }
+            }
+            // Imported configurations bring their beans along
+            if features.contains(&Feature::Import) && features.contains(&Feature::Bean) {
+                let imported = reachable_beans(&ids, class)
+                    .into_iter()
+                    .filter(|(owner, _)| !std::ptr::eq(*owner, class));
+                for (owner, bean) in imported {
+                    let edge =
+                        Edge::new(id.clone(), ids.resolve(owner, bean.class()), EdgeKind::Bean)
+                            .with_label(format!("{} (imported)", EdgeKind::Bean.label()));
+                    if keys.insert((edge.from.clone(), edge.to.clone(), edge.kind)) {
+                        edges.push(edge);
+                    }
+                }
+            }
+            // Remote services called through feign clients
+            for service in injected.iter().filter_map(|i| feign_clients.get(i)) {
+                let edge = Edge::new(id.clone(), service.to_string(), EdgeKind::FeignClient);
+                if keys.insert((edge.from.clone(), edge.to.clone(), edge.kind)) {
+                    edges.push(edge);
+                }
+            }
+            let implemented = class
+                .interfaces()
+                .iter()
+                .map(|i| ids.resolve(class, i))
+                .collect();
+            interfaces.insert(ids.id(class), implemented);
+        }
+        Graph {
+            nodes,
+            edges,
+            interfaces,
+        }
+    }
+
+    /// Leaves out the nodes with the given ids, along with the edges to and from them
+    /// and the edges out of the packages and beans that only they led to.
+    pub fn without_nodes(mut self, ids: &HashSet<String>) -> Self {
+        self.nodes.retain(|n| !ids.contains(&n.id));
+        self.edges
+            .retain(|e| !ids.contains(&e.from) && !ids.contains(&e.to));
+        // Scanned packages and beans are only kept along with an edge to them
+        let reached: HashSet<String> = self
+            .edges
+            .iter()
+            .filter(|e| matches!(e.kind, EdgeKind::ComponentScan | EdgeKind::Bean))
+            .map(|e| e.to.clone())
+            .collect();
+        self.edges.retain(|e| {
+            !matches!(e.kind, EdgeKind::Contains | EdgeKind::BeanInjection)
+                || reached.contains(&e.from)
+        });
+        for implementations in self.interfaces.values_mut() {
+            implementations.retain(|i| !ids.contains(i));
+        }
+        self
+    }
+
+    /// Colors the nodes of components with the color of their type.
+    pub fn with_node_colors<'a>(mut self, color: impl Fn(&ComponentType) -> &'a str) -> Self {
+        for node in &mut self.nodes {
+            node.color = node.component_type.as_ref().map(|t| color(t).to_string());
+        }
+        self
+    }
+
+    /// Replaces each edge with the one `f` gives for it.
+    pub fn map_edges(self, f: impl FnMut(Edge) -> Edge) -> Self {
+        Graph {
+            edges: self.edges.into_iter().map(f).collect(),
+            ..self
+        }
+    }
+
+    /// Collapses the edges between the same pair of nodes, see [`merge_parallel_edges`].
+    pub fn merge_parallel_edges(self) -> Self {
+        Graph {
+            edges: merge_parallel_edges(self.edges),
+            ..self
+        }
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        self.nodes.as_ref()
+    }
+
+    fn node_ids(&self) -> HashSet<&str> {
+        self.nodes.iter().map(Node::id).collect()
+    }
+
+    fn edge_keys(&self) -> HashSet<(&str, &str, EdgeKind)> {
+        self.edges.iter().map(Edge::key).collect()
+    }
+
+    /// Compares the graph to an earlier one. Nodes are the same if they have the same id,
+    /// and edges if they join the same nodes and are of the same kind.
+    pub fn compare(&self, baseline: &Graph) -> GraphDiff {
+        let (current_nodes, baseline_nodes) = (self.node_ids(), baseline.node_ids());
+        let (current_edges, baseline_edges) = (self.edge_keys(), baseline.edge_keys());
+        let missing_nodes = |graph: &Graph, ids: &HashSet<&str>| {
+            graph
+                .nodes
+                .iter()
+                .filter(|n| !ids.contains(n.id()))
+                .cloned()
+                .collect()
+        };
+        let missing_edges = |graph: &Graph, keys: &HashSet<(&str, &str, EdgeKind)>| {
+            graph
+                .edges
+                .iter()
+                .filter(|e| !keys.contains(&e.key()))
+                .cloned()
+                .collect()
+        };
+        GraphDiff {
+            added_nodes: missing_nodes(self, &baseline_nodes),
+            removed_nodes: missing_nodes(baseline, &current_nodes),
+            added_edges: missing_edges(self, &baseline_edges),
+            removed_edges: missing_edges(baseline, &current_edges),
+        }
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        self.edges.as_ref()
+    }
+
+    /// Maps each injected type to the classes injecting it.
+    fn reverse_adjacency(&self) -> HashMap<&str, Vec<&str>> {
+        let mut reverse: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in self.edges.iter().filter(|e| e.kind().is_injection()) {
+            reverse.entry(edge.to()).or_default().push(edge.from());
+        }
+        reverse
+    }
+
+    /// Finds the classes that depend on `name`, either directly or through
+    /// one of the interfaces it implements. With `transitive`, dependents of
+    /// dependents are included as well.
+    pub fn reverse_dependencies(&self, name: &str, transitive: bool) -> BTreeSet<String> {
+        let reverse = self.reverse_adjacency();
+        let mut dependents = BTreeSet::new();
+        let mut visited = BTreeSet::new();
+        let mut queue = VecDeque::from([name.to_string()]);
+        while let Some(current) = queue.pop_front() {
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+            let mut targets = vec![current.as_str()];
+            if let Some(interfaces) = self.interfaces.get(&current) {
+                targets.extend(interfaces.iter().map(|i| i.as_str()));
+            }
+            // Injections of an interface are drawn to its implementations
+            targets.extend(
+                self.interfaces
+                    .iter()
+                    .filter(|(_, interfaces)| interfaces.contains(&current))
+                    .map(|(implementation, _)| implementation.as_str()),
+            );
+            for target in targets {
+                for dependent in reverse.get(target).into_iter().flatten() {
+                    if dependents.insert(dependent.to_string()) && transitive {
+                        queue.push_back(dependent.to_string());
+                    }
+                }
+            }
+        }
+        dependents
+    }
+}
+
+/// Collects the bean definitions of `class` and of every configuration it imports,
+/// following imports transitively, along with the class defining each of them.
+pub fn reachable_beans<'a>(ids: &NodeIds<'a>, class: &'a Class) -> Vec<(&'a Class, &'a Bean)> {
+    let mut beans = Vec::new();
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([class]);
+    while let Some(current) = queue.pop_front() {
+        // Imports may form a cycle
+        if !visited.insert(ids.id(current)) {
+            continue;
+        }
+        beans.extend(current.bean_defs().iter().map(|b| (current, b)));
+        for import in current.imports() {
+            match ids.resolve_import(import) {
+                Some(imported) => queue.push_back(imported),
+                None => tracing::debug!("Unresolved import {} in {}", import, current.name()),
+            }
+        }
+    }
+    beans
+}
+
+/// Counts the dependencies between classes of different packages, keyed by
+/// the depending package and the package depended on.
+pub fn package_dependencies(
+    classes: &[Class],
+    features: &Features,
+) -> BTreeMap<(String, String), usize> {
+    let ids = NodeIds::new(classes);
+    let packages: HashMap<String, &str> =
+        classes.iter().map(|c| (ids.id(c), c.package())).collect();
+    let mut dependencies = BTreeMap::new();
+    let graph = Graph::with_features(classes, features);
+    for edge in graph.edges().iter().filter(|e| e.kind().is_injection()) {
+        let (Some(from), Some(to)) = (packages.get(edge.from()), packages.get(edge.to())) else {
+            continue;
+        };
+        if from != to {
+            *dependencies
+                .entry((from.to_string(), to.to_string()))
+                .or_default() += 1;
+        }
+    }
+    dependencies
+}
+
+/// Groups classes by component type, leaving out types without any classes.
+pub fn classes_by_type(classes: &[Class]) -> Vec<(ComponentType, Vec<Class>)> {
+    ComponentType::iter()
+        .map(|component_type| {
+            let of_type = classes
+                .iter()
+                .filter(|c| c.component_type() == Some(&component_type))
+                .cloned()
+                .collect::<Vec<_>>();
+            (component_type, of_type)
+        })
+        .filter(|(_, of_type)| !of_type.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use strum::IntoEnumIterator;
+
+    use crate::{
+        autowired::{Autowired, Qualifier},
+        bean::{Bean, Parameter},
+        class::{Class, ClassBuilder},
+        component_type::ComponentType,
+        feature::Features,
+    };
+
+    use super::{
+        classes_by_type, merge_parallel_edges, package_dependencies, reachable_beans, Edge,
+        EdgeKind, Graph, GraphDiff, Node, NodeIds,
+    };
+
+    /// A class named `name` in `a.b.c`, to add to before building.
+    fn named(name: &str) -> ClassBuilder {
+        let mut builder = ClassBuilder::default();
+        builder.package("a.b.c".to_string()).name(name.to_string());
+        builder
+    }
+
+    fn class(name: &str, autowires: &[&str], interfaces: &[&str]) -> Class {
+        named(name)
+            .autowires(
+                autowires
+                    .iter()
+                    .map(|a| Autowired::new(a.to_string(), a.to_lowercase()))
+                    .collect(),
+            )
+            .interfaces(interfaces.iter().map(|i| i.to_string()).collect())
+            .build()
+            .unwrap()
+    }
+
+    fn names(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn repeated_injection_gives_one_edge() {
+        let classes = vec![class("A", &["B", "B"], &[]), class("B", &[], &[])];
+        let graph = Graph::new(&classes);
+        let edges: Vec<_> = graph.edges().iter().map(Edge::key).collect();
+        assert_eq!(vec![("A", "B", EdgeKind::Autowired)], edges);
+    }
+
+    #[test]
+    fn reverse_dependencies_direct() {
+        let classes = vec![
+            class("Repo", &[], &[]),
+            class("Service", &["Repo"], &[]),
+            class("Controller", &["Service"], &[]),
+        ];
+        let graph = Graph::new(&classes);
+        assert_eq!(
+            names(&["Service"]),
+            graph.reverse_dependencies("Repo", false)
+        );
+    }
+
+    #[test]
+    fn reverse_dependencies_transitive() {
+        let classes = vec![
+            class("Repo", &[], &[]),
+            class("Service", &["Repo"], &[]),
+            class("Controller", &["Service"], &[]),
+        ];
+        let graph = Graph::new(&classes);
+        assert_eq!(
+            names(&["Controller", "Service"]),
+            graph.reverse_dependencies("Repo", true)
+        );
+    }
+
+    #[test]
+    fn reverse_dependencies_through_interface() {
+        let service = named("Service")
+            .parameters(vec![Parameter {
+                annotations: vec![],
+                class: "IRepo".to_string(),
+                name: "repo".to_string(),
+                collection: false,
+                qualifier: None,
+            }])
+            .build()
+            .unwrap();
+        let classes = vec![class("RepoImpl", &[], &["IRepo"]), service];
+        let graph = Graph::new(&classes);
+        assert_eq!(
+            names(&["Service"]),
+            graph.reverse_dependencies("RepoImpl", false)
+        );
+        assert_eq!(
+            names(&["Service"]),
+            graph.reverse_dependencies("IRepo", false)
+        );
+    }
+
+    #[test]
+    fn edges_of_every_kind() {
+        let mut classes = vec![
+            config("AppConfig", &["DbConfig"], &["DataSource"]),
+            config("DbConfig", &[], &[]),
+            class("RepoImpl", &[], &["IRepo"]),
+            class("Service", &["IRepo"], &[]),
+        ];
+        let edges = |classes: &[Class], features: &str| {
+            Graph::with_features(classes, &features.parse().unwrap())
+                .edges()
+                .iter()
+                .map(|e| (e.from().to_string(), e.to().to_string(), e.kind()))
+                .collect::<Vec<_>>()
+        };
+        let edge = |from: &str, to: &str, kind| (from.to_string(), to.to_string(), kind);
+        assert_eq!(
+            vec![
+                edge("AppConfig", "DbConfig", EdgeKind::Import),
+                edge("AppConfig", "DataSource", EdgeKind::Bean),
+                edge("IRepo", "RepoImpl", EdgeKind::Implements),
+                edge("Service", "RepoImpl", EdgeKind::Autowired),
+            ],
+            edges(&classes, "import,bean,autowired")
+        );
+
+        // Implementations combined with their interface are drawn as it
+        classes.drain(..2);
+        let graph = Graph::with_features(
+            &classes,
+            &"autowired,combineimplandinterface".parse().unwrap(),
+        );
+        assert_eq!("IRepo", graph.nodes()[0].id());
+        assert_eq!(
+            vec![edge("Service", "IRepo", EdgeKind::Autowired)],
+            edges(&classes, "autowired,combineimplandinterface")
+        );
+    }
+
+    #[test]
+    fn injected_interface_resolves_to_implementations() {
+        let controller = |qualifier: Option<&str>| {
+            named("PaymentController")
+                .autowires(vec![Autowired::new(
+                    "PaymentService".to_string(),
+                    "paymentService".to_string(),
+                )
+                .with_qualifier(qualifier.map(|q| Qualifier::Name(q.to_string())))])
+                .build()
+                .unwrap()
+        };
+        let implementations = [
+            class("CardPaymentService", &[], &["PaymentService"]),
+            class("InvoicePaymentService", &[], &["PaymentService"]),
+        ];
+        let edges = |classes: &[Class]| {
+            Graph::new(classes)
+                .edges()
+                .iter()
+                .filter(|e| e.kind().is_injection())
+                .map(|e| (e.to().to_string(), e.ambiguous()))
+                .collect::<Vec<_>>()
+        };
+
+        let classes = [&implementations[..], &[controller(None)]].concat();
+        assert_eq!(
+            vec![
+                ("CardPaymentService".to_string(), true),
+                ("InvoicePaymentService".to_string(), true)
+            ],
+            edges(&classes)
+        );
+
+        let classes = [
+            &implementations[..],
+            &[controller(Some("invoicePaymentService"))],
+        ]
+        .concat();
+        assert_eq!(
+            vec![("InvoicePaymentService".to_string(), false)],
+            edges(&classes)
+        );
+
+        // An explicit bean name replaces the default one
+        let cards = named("CardPaymentService")
+            .interfaces(vec!["PaymentService".to_string()])
+            .bean_name(Some("cards".to_string()))
+            .build()
+            .unwrap();
+        let classes = [cards, implementations[1].clone(), controller(Some("cards"))];
+        assert_eq!(
+            vec![("CardPaymentService".to_string(), false)],
+            edges(&classes)
+        );
+
+        // A @Primary @Bean method constructing an implementation picks it
+        let config = named("PaymentConfig")
+            .bean_defs(vec![Bean::new(
+                "PaymentService".to_string(),
+                "invoices".to_string(),
+                vec![],
+            )
+            .with_implementation(Some("InvoicePaymentService".to_string()))
+            .with_primary(true)])
+            .build()
+            .unwrap();
+        let classes = [&implementations[..], &[config, controller(None)]].concat();
+        assert_eq!(
+            vec![("InvoicePaymentService".to_string(), false)],
+            edges(&classes)
+        );
+    }
+
+    #[test]
+    fn compare_to_baseline() {
+        let baseline = Graph::new(&[
+            class("Repo", &[], &[]),
+            class("Cache", &[], &[]),
+            class("Service", &["Repo", "Cache"], &[]),
+        ]);
+        let current = Graph::new(&[
+            class("Repo", &[], &[]),
+            class("Client", &[], &[]),
+            class("Service", &["Repo", "Client"], &[]),
+        ]);
+        let diff = current.compare(&baseline);
+        let ids = |nodes: &[Node]| nodes.iter().map(|n| n.id().to_string()).collect::<Vec<_>>();
+        let ends = |edges: &[Edge]| {
+            edges
+                .iter()
+                .map(|e| (e.from().to_string(), e.to().to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["Client"], ids(&diff.added_nodes));
+        assert_eq!(vec!["Cache"], ids(&diff.removed_nodes));
+        assert_eq!(
+            vec![("Service".to_string(), "Client".to_string())],
+            ends(&diff.added_edges)
+        );
+        assert_eq!(
+            vec![("Service".to_string(), "Cache".to_string())],
+            ends(&diff.removed_edges)
+        );
+        assert_eq!(GraphDiff::default(), current.compare(&current));
+    }
+
+    #[test]
+    fn compare_finds_added_import() {
+        let baseline = Graph::new(&[config("AppConfig", &[], &[]), config("DbConfig", &[], &[])]);
+        let current = Graph::new(&[
+            config("AppConfig", &["DbConfig"], &[]),
+            config("DbConfig", &[], &[]),
+        ]);
+        let diff = current.compare(&baseline);
+        let edges: Vec<_> = diff
+            .added_edges
+            .iter()
+            .map(|e| (e.from(), e.to(), e.kind()))
+            .collect();
+        assert_eq!(vec![("AppConfig", "DbConfig", EdgeKind::Import)], edges);
+        assert!(diff.added_nodes.is_empty() && diff.removed_nodes.is_empty());
+        assert!(diff.removed_edges.is_empty());
+    }
+
+    #[test]
+    fn parallel_edges_are_merged() {
+        let edge = |to: &str, kind| Edge::new("Foo".to_string(), to.to_string(), kind);
+        let merged = merge_parallel_edges([
+            edge("Bar", EdgeKind::Autowired),
+            edge("Baz", EdgeKind::Autowired),
+            edge("Bar", EdgeKind::ConstructorInjection).with_ambiguous(true),
+            edge("Bar", EdgeKind::Autowired),
+        ]);
+        let merged: Vec<_> = merged
+            .iter()
+            .map(|e| (e.to(), e.kind(), e.label(), e.ambiguous()))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    "Bar",
+                    EdgeKind::Autowired,
+                    "@Autowired ×2, Constructor",
+                    true
+                ),
+                ("Baz", EdgeKind::Autowired, "@Autowired", false),
+            ],
+            merged
+        );
+    }
+
+    #[test]
+    fn edge_kind_labels() {
+        let labels: Vec<_> = EdgeKind::iter().map(|k| (k, k.label())).collect();
+        assert_eq!(
+            vec![
+                (EdgeKind::Import, "@Import"),
+                (EdgeKind::ComponentScan, "@ComponentScan"),
+                (EdgeKind::Contains, "contains"),
+                (EdgeKind::Autowired, "@Autowired"),
+                (EdgeKind::ConstructorInjection, "Constructor"),
+                (EdgeKind::BeanInjection, "@Autowired (CI)"),
+                (EdgeKind::Bean, "@Bean"),
+                (EdgeKind::Implements, "impl"),
+                (EdgeKind::Value, "@Value"),
+                (EdgeKind::Entity, "entity"),
+                (EdgeKind::FeignClient, "@FeignClient"),
+            ],
+            labels
+        );
+    }
+
+    #[test]
+    fn node_from_class() {
+        let service = named("FooService")
+            .component_type(Some(ComponentType::Service))
+            .build()
+            .unwrap();
+        let node = Node::from(&service);
+        assert_eq!("a.b.c.FooService", node.id());
+        assert_eq!("FooService", node.label());
+        assert_eq!(Some(&ComponentType::Service), node.component_type());
+        assert_eq!(Some(ComponentType::Service.color_code()), node.color());
+
+        let node = Node::from(&class("Plain", &[], &[]));
+        assert_eq!(None, node.component_type());
+        assert_eq!(None, node.color());
+
+        // Node ids only qualify the names that several classes share
+        let classes = [service, class("Plain", &[], &[])];
+        let node = NodeIds::new(&classes).node(&classes[0]);
+        assert_eq!("FooService", node.id());
+        assert_eq!(Some(ComponentType::Service.color_code()), node.color());
+    }
+
+    #[test]
+    fn same_named_classes_are_distinct_nodes() {
+        let user = named("Bar")
+            .java_imports(vec!["x.y.Foo".to_string()])
+            .autowires(vec![Autowired::new("Foo".to_string(), "foo".to_string())])
+            .build()
+            .unwrap();
+        let classes = vec![
+            named("Foo").build().unwrap(),
+            named("Foo").package("x.y".to_string()).build().unwrap(),
+            user,
+        ];
+        let ids = NodeIds::new(&classes);
+        assert_eq!("a.b.c.Foo", ids.id(&classes[0]));
+        assert_eq!("x.y.Foo", ids.id(&classes[1]));
+        assert_eq!("Bar", ids.id(&classes[2]));
+        assert_eq!("x.y.Foo", ids.resolve(&classes[2], "Foo"));
+        assert_eq!("a.b.c.Foo", ids.resolve(&classes[1], "a.b.c.Foo"));
+        assert_eq!("Baz", ids.resolve(&classes[2], "Baz"));
+
+        let graph = Graph::new(&classes);
+        assert_eq!(
+            names(&["Bar"]),
+            graph.reverse_dependencies("x.y.Foo", false)
+        );
+        assert!(graph.reverse_dependencies("a.b.c.Foo", false).is_empty());
+    }
+
+    #[test]
+    fn package_dependencies_are_aggregated() {
+        let in_package = |package: &str, name: &str, autowires: &[&str]| {
+            named(name)
+                .package(package.to_string())
+                .autowires(
+                    autowires
+                        .iter()
+                        .map(|a| Autowired::new(a.to_string(), a.to_lowercase()))
+                        .collect(),
+                )
+                .build()
+                .unwrap()
+        };
+        let classes = vec![
+            in_package("a.web", "FooController", &["FooService", "BarService"]),
+            in_package("a.web", "BarController", &["BarService", "FooController"]),
+            in_package("a.service", "FooService", &["BarService"]),
+            in_package("a.service", "BarService", &["Unknown"]),
+        ];
+        let dependencies = package_dependencies(&classes, &Features::default());
+        assert_eq!(
+            vec![(("a.web".to_string(), "a.service".to_string()), 3)],
+            dependencies.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    fn config(name: &str, imports: &[&str], beans: &[&str]) -> Class {
+        named(name)
+            .imports(imports.iter().map(|i| i.to_string()).collect())
+            .bean_defs(
+                beans
+                    .iter()
+                    .map(|b| Bean::new(b.to_string(), b.to_lowercase(), vec![]))
+                    .collect(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn resolve_import_by_simple_and_qualified_name() {
+        let classes = vec![config("B", &[], &[])];
+        let ids = NodeIds::new(&classes);
+        assert_eq!(Some(&classes[0]), ids.resolve_import("B"));
+        assert_eq!(Some(&classes[0]), ids.resolve_import("a.b.c.B"));
+        assert_eq!(None, ids.resolve_import("x.y.B"));
+        assert_eq!(None, ids.resolve_import("C"));
+    }
+
+    #[test]
+    fn reachable_beans_include_imported_configuration() {
+        let classes = vec![
+            config("A", &["B"], &[]),
+            config("B", &["C"], &["MyBean"]),
+            config("C", &["A"], &["OtherBean"]),
+        ];
+        let ids = NodeIds::new(&classes);
+        let beans: Vec<&str> = reachable_beans(&ids, &classes[0])
+            .into_iter()
+            .map(|(_, b)| b.class())
+            .collect();
+        assert_eq!(vec!["MyBean", "OtherBean"], beans);
+
+        let edges: Vec<_> = Graph::new(&classes)
+            .edges()
+            .iter()
+            .filter(|e| e.from() == "A" && e.kind() == EdgeKind::Bean)
+            .map(|e| (e.to().to_string(), e.label().to_string()))
+            .collect();
+        let imported = |bean: &str| (bean.to_string(), "@Bean (imported)".to_string());
+        assert_eq!(vec![imported("MyBean"), imported("OtherBean")], edges);
+    }
+
+    #[test]
+    fn injected_bean_type_resolves_to_bean_node() {
+        let classes = vec![
+            config("AppConfig", &[], &["com.acme.Foo"]),
+            class("FooService", &["Foo"], &[]),
+            named("FooClient")
+                .package("x.y".to_string())
+                .parameters(vec![Parameter {
+                    annotations: vec![],
+                    class: "com.acme.Foo".to_string(),
+                    name: "foo".to_string(),
+                    collection: false,
+                    qualifier: None,
+                }])
+                .build()
+                .unwrap(),
+        ];
+        let bean = NodeIds::new(&classes).resolve(&classes[0], "com.acme.Foo");
+        let targets: Vec<_> = Graph::new(&classes)
+            .edges()
+            .iter()
+            .filter(|e| e.kind().is_injection())
+            .map(|e| (e.from().to_string(), e.to().to_string()))
+            .collect();
+        assert_eq!("com.acme.Foo", bean);
+        assert_eq!(
+            vec![
+                ("FooService".to_string(), bean.clone()),
+                ("FooClient".to_string(), bean)
+            ],
+            targets
+        );
+    }
+
+    #[test]
+    fn injected_bean_type_resolves_by_qualified_name() {
+        let service = |imports: &[&str]| {
+            named("FooService")
+                .java_imports(imports.iter().map(|i| i.to_string()).collect())
+                .build()
+                .unwrap()
+        };
+        let classes = vec![
+            config("AcmeConfig", &[], &["com.acme.Foo"]),
+            config("OtherConfig", &[], &["org.other.Foo"]),
+            config("BarConfig", &[], &["org.other.Bar"]),
+        ];
+        let ids = NodeIds::new(&classes);
+        let importing = service(&["org.other.Foo"]);
+        assert_eq!("org.other.Foo", ids.resolve(&importing, "Foo"));
+        assert_eq!("com.acme.Foo", ids.resolve(&importing, "com.acme.Foo"));
+        // Several beans share the simple name, so it cannot tell them apart
+        assert_eq!("Foo", ids.resolve(&service(&[]), "Foo"));
+        assert_eq!("org.other.Bar", ids.resolve(&service(&[]), "Bar"));
+    }
+
+    #[test]
+    fn injected_implementation_resolves_to_bean_node() {
+        let config = named("DbConfig")
+            .bean_defs(vec![Bean::new(
+                "DataSource".to_string(),
+                "dataSource".to_string(),
+                vec![],
+            )
+            .with_implementation(Some("HikariDataSource".to_string()))])
+            .build()
+            .unwrap();
+        let classes = [config, class("Pool", &["HikariDataSource"], &[])];
+        let targets: Vec<_> = Graph::new(&classes)
+            .edges()
+            .iter()
+            .filter(|e| e.kind().is_injection())
+            .map(|e| e.to().to_string())
+            .collect();
+        assert_eq!(vec!["DataSource"], targets);
+
+        let classes = [
+            named("DbConfig")
+                .bean_defs(vec![Bean::new(
+                    "DataSource".to_string(),
+                    "dataSource".to_string(),
+                    vec![],
+                )])
+                .build()
+                .unwrap(),
+            classes[1].clone(),
+        ];
+        let targets: Vec<_> = Graph::new(&classes)
+            .edges()
+            .iter()
+            .filter(|e| e.kind().is_injection())
+            .map(|e| e.to().to_string())
+            .collect();
+        assert_eq!(vec!["HikariDataSource"], targets);
+    }
+
+    #[test]
+    fn classes_by_type_skips_empty_types() {
+        let classes = vec![
+            named("FooService")
+                .component_type(Some(ComponentType::Service))
+                .build()
+                .unwrap(),
+            named("FooController")
+                .component_type(Some(ComponentType::Controller))
+                .build()
+                .unwrap(),
+            named("BarService")
+                .component_type(Some(ComponentType::Service))
+                .build()
+                .unwrap(),
+            class("Plain", &[], &[]),
+        ];
+        let grouped = classes_by_type(&classes);
+        let grouped: Vec<(ComponentType, Vec<&str>)> = grouped
+            .iter()
+            .map(|(t, cs)| (t.clone(), cs.iter().map(|c| c.name()).collect()))
+            .collect();
+        assert_eq!(
+            vec![
+                (ComponentType::Controller, vec!["FooController"]),
+                (ComponentType::Service, vec!["FooService", "BarService"]),
+            ],
+            grouped
+        );
+    }
+}
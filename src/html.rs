@@ -0,0 +1,161 @@
+//! Self-contained HTML page showing the rendered graph.
+
+use std::io::{self, Write};
+
+use crate::{
+    class::Class,
+    graph::{classes_by_type, NodeIds},
+    render::{render, RenderOptions, Theme, DARK_BACKGROUND, DARK_FOREGROUND},
+};
+
+const HEADER: &str = r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Spring components</title>
+<script src="https://unpkg.com/d3@7/dist/d3.min.js"></script>
+<script src="https://unpkg.com/@hpcc-js/wasm@2/dist/graphviz.umd.js"></script>
+<script src="https://unpkg.com/d3-graphviz@5/build/d3-graphviz.min.js"></script>
+<style>
+body { display: flex; margin: 0; font-family: sans-serif; }
+nav { min-width: 16em; padding: 1em; border-right: 1px solid #ccc; overflow-y: auto; height: 100vh; box-sizing: border-box; }
+nav li { cursor: pointer; }
+nav li.selected { font-weight: bold; }
+#graph { flex: 1; height: 100vh; }
+#graph svg { width: 100%; height: 100%; }
+</style>
+"##;
+
+const FOOTER: &str = r##"<div id="graph"></div>
+<script>
+function select(name) {
+    document.querySelectorAll("nav li").forEach(function (li) {
+        li.classList.toggle("selected", li.dataset.node === name);
+    });
+    d3.selectAll("#graph .node").style("opacity", function () {
+        return name === null || this.__data__.key === name ? 1 : 0.3;
+    });
+}
+d3.select("#graph")
+    .graphviz()
+    .zoom(true)
+    .renderDot(document.getElementById("dot").textContent)
+    .on("end", function () {
+        d3.selectAll("#graph .node").on("click", function () {
+            select(this.__data__.key);
+        });
+    });
+document.querySelectorAll("nav li").forEach(function (li) {
+    li.addEventListener("click", function () { select(li.dataset.node); });
+});
+</script>
+</body>
+</html>
+"##;
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes an HTML page with the DOT graph, rendered in the browser by d3-graphviz,
+/// next to a sidebar listing the components by type.
+pub fn render_html(
+    out: &mut impl Write,
+    classes: &[Class],
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let mut dot = Vec::new();
+    render(&mut dot, classes, options)?;
+    let dot = String::from_utf8_lossy(&dot);
+
+    write!(out, "{}", HEADER)?;
+    // The page around the graph follows its theme
+    if options.theme() == Theme::Dark {
+        writeln!(
+            out,
+            "<style>\nbody {{ background: {}; color: {}; }}\nnav {{ border-color: #444; }}\n</style>",
+            DARK_BACKGROUND, DARK_FOREGROUND
+        )?;
+    }
+    writeln!(out, "</head>\n<body>")?;
+    writeln!(out, "<nav>")?;
+    let ids = NodeIds::new(classes);
+    for (component_type, of_type) in classes_by_type(classes) {
+        if !options.shows(&component_type) {
+            continue;
+        }
+        writeln!(out, "<h3>@{:?}</h3>", component_type)?;
+        writeln!(out, "<ul>")?;
+        for node in of_type.iter().map(|c| ids.node(c)) {
+            writeln!(
+                out,
+                "<li data-node=\"{}\">{}</li>",
+                escape(node.id()),
+                escape(node.label())
+            )?;
+        }
+        writeln!(out, "</ul>")?;
+    }
+    writeln!(out, "</nav>")?;
+    writeln!(out, "<script id=\"dot\" type=\"text/vnd.graphviz\">")?;
+    // The DOT source is raw text, so only a closing tag could end the block early
+    write!(out, "{}", dot.replace("</", "<\\/"))?;
+    writeln!(out, "</script>")?;
+    write!(out, "{}", FOOTER)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        class::ClassBuilder,
+        component_type::ComponentType,
+        render::{RenderOptions, RenderOptionsBuilder, Theme},
+    };
+
+    use super::render_html;
+
+    #[test]
+    fn render_html_embeds_dot_and_sidebar() {
+        let class = ClassBuilder::default()
+            .package("a.b.c".to_string())
+            .name("FooService".to_string())
+            .component_type(Some(ComponentType::Service))
+            .build()
+            .unwrap();
+        let mut out = Vec::new();
+        render_html(
+            &mut out,
+            std::slice::from_ref(&class),
+            &RenderOptions::default(),
+        )
+        .unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("<script id=\"dot\" type=\"text/vnd.graphviz\">\n// features: "));
+        assert!(output.contains("\ndigraph Components {"));
+        assert!(output.contains("<h3>@Service</h3>"));
+        assert!(output.contains("<li data-node=\"FooService\">FooService</li>"));
+        assert!(!output.contains("background"));
+
+        let options = RenderOptionsBuilder::default()
+            .theme(Theme::Dark)
+            .build()
+            .unwrap();
+        let mut out = Vec::new();
+        render_html(&mut out, std::slice::from_ref(&class), &options).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("body { background: #1e1e1e; color: #e0e0e0; }"));
+        assert!(output.contains("    bgcolor=\"#1e1e1e\";"));
+
+        let options = RenderOptionsBuilder::default()
+            .only_types(vec![ComponentType::Controller])
+            .build()
+            .unwrap();
+        let mut out = Vec::new();
+        render_html(&mut out, &[class], &options).unwrap();
+        assert!(!String::from_utf8(out).unwrap().contains("FooService"));
+    }
+}
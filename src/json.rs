@@ -0,0 +1,50 @@
+//! The graph as JSON, for other tools and for comparing a later graph against.
+
+use std::io::{self, Read, Write};
+
+use crate::{class::Class, graph::Graph, render::RenderOptions};
+
+/// Writes the nodes and edges of the enabled features as JSON.
+pub fn render_json(
+    out: &mut impl Write,
+    classes: &[Class],
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let graph = options.graph(classes);
+    serde_json::to_writer_pretty(&mut *out, &graph)?;
+    writeln!(out)
+}
+
+/// Reads a graph written by [`render_json`].
+pub fn read_graph(reader: impl Read) -> serde_json::Result<Graph> {
+    serde_json::from_reader(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        autowired::Autowired, class::ClassBuilder, component_type::ComponentType, graph::Graph,
+        render::RenderOptions,
+    };
+
+    use super::{read_graph, render_json};
+
+    #[test]
+    fn graph_round_trips_through_json() {
+        let classes = [ClassBuilder::default()
+            .package("a.b.c".to_string())
+            .name("FooService".to_string())
+            .component_type(Some(ComponentType::Service))
+            .autowires(vec![Autowired::new(
+                "FooRepository".to_string(),
+                "fooRepository".to_string(),
+            )])
+            .build()
+            .unwrap()];
+        let mut buf = Vec::new();
+        render_json(&mut buf, &classes, &RenderOptions::default()).unwrap();
+        let graph = read_graph(buf.as_slice()).unwrap();
+        assert_eq!(Graph::new(&classes), graph);
+        assert_eq!(1, graph.edges().len());
+    }
+}
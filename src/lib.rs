@@ -4,4 +4,14 @@ pub mod bean;
 pub mod class;
 pub mod component_scan;
 pub mod component_type;
+pub mod feature;
+pub mod feign_client;
+pub mod graph;
+pub mod html;
 pub mod import;
+pub mod json;
+pub mod matrix;
+pub mod palette;
+pub mod render;
+pub mod stats;
+pub mod walk;
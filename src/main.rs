@@ -1,321 +1,1531 @@
-use clap::Parser;
-use ignore::{DirEntry, Walk};
-use itertools::Itertools;
+use clap::{
+    error::ErrorKind, parser::ValueSource, ArgGroup, ArgMatches, Args as _, CommandFactory,
+    FromArgMatches, Parser, Subcommand,
+};
+use notify::{RecursiveMode, Watcher};
 use spring_visualizer::{
-    class::{parse_class, Class},
+    class::{
+        parse_class_with_options, sort_classes, Class, ParseOptions, ParseOptionsBuilder,
+        WRAPPER_TYPES,
+    },
     component_type::ComponentType,
+    feature::{Feature, Features},
+    graph::Graph,
+    html::render_html,
+    json::{read_graph, render_json},
+    matrix::render_csv_matrix,
+    palette::{ColorOverrides, Palette},
+    render::{
+        render, render_comparison, Direction, Format, ImageFormat, LabelStyle, RenderOptions,
+        RenderOptionsBuilder, Theme,
+    },
+    stats::{ParseErrorPolicy, ParseStats, Phase, SkipReason, Timings},
+    walk::{is_source_file, read_file, source_files, WalkOptions, WalkOptionsBuilder},
 };
 use std::{
+    collections::{HashMap, HashSet},
     error::Error,
     ffi::OsString,
-    fmt::Display,
-    fs::File,
-    io::{BufReader, Read},
-    path::Path,
-    str::FromStr,
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    process::{self, Stdio},
+    sync::mpsc,
+    time::Duration,
 };
-use strum::{EnumIter, IntoEnumIterator};
+use strum::IntoEnumIterator;
 use tracing_subscriber::EnvFilter;
 
-fn read_file(path: &Path) -> Result<String, Box<dyn Error>> {
-    // Read file contents
-    let f = File::open(path)?;
-    let mut f = BufReader::new(f);
-    let mut buf = String::new();
-    f.read_to_string(&mut buf)?;
-    Ok(buf)
-}
-
-fn javafiles(package: &str) -> impl Iterator<Item = DirEntry> + '_ {
-    Walk::new("./")
-        .filter_map(|e| e.ok())
-        .filter_map(move |entry| {
-            // Entry must be a file
-            let path = entry.path();
-            if !path.is_file() {
-                return None;
-            }
-
-            // Must have a .java extension
-            let ext = entry.path().extension();
-            let java_ext = OsString::from_str("java").expect("is a valid OsStr");
-            let is_java = ext == Some(&java_ext);
-
-            // Path must contain user search
-            let path = path.to_str().or_else(|| {
-                tracing::warn!("Path is not valid UTF-8: {:?}", path);
-                None
-            })?;
-            let is_right_package = path.contains(package);
+fn print_features() {
+    for feature in Feature::iter() {
+        println!("{:<28}{}", feature.to_string(), feature.description());
+        if !feature.annotations().is_empty() {
+            println!(
+                "{:<28}Annotations: {}",
+                "",
+                feature.annotations().join(", ")
+            );
+        }
+    }
+}
 
-            if is_java && is_right_package {
-                Some(entry)
-            } else {
-                None
-            }
-        })
+fn parse_alias(s: &str) -> Result<(String, ComponentType), String> {
+    let (name, component_type) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=TYPE, got {}", s))?;
+    let component_type = component_type
+        .trim()
+        .parse()
+        .map_err(|_| format!("unknown component type {}", component_type))?;
+    Ok((
+        name.trim().trim_start_matches('@').to_string(),
+        component_type,
+    ))
 }
 
-fn print_legend() {
-    println!("    # Legend");
-    for component_type in ComponentType::iter() {
-        println!(
-            "    \"@{:?}\" [fillcolor=\"{}\",style=filled];",
-            component_type,
-            component_type.color_code()
-        );
+/// Writes the graph in the given format, or its differences to `baseline` as DOT.
+fn write_graph(
+    out: &mut impl Write,
+    classes: &[Class],
+    options: &RenderOptions,
+    format: &Format,
+    baseline: Option<&Graph>,
+) -> io::Result<()> {
+    if let Some(baseline) = baseline {
+        let current = options.graph(classes);
+        return render_comparison(out, &current, baseline, options);
     }
-
-    println!();
-
-    println!("    # Align legend");
-    for (cur, next) in ComponentType::iter().zip(ComponentType::iter().skip(1)) {
-        println!(r#"    "@{:?}" -> "@{:?}" [style=invis];"#, cur, next);
+    match format {
+        Format::Dot => render(out, classes, options),
+        Format::Html => render_html(out, classes, options),
+        Format::CsvMatrix => render_csv_matrix(out, classes, options),
+        Format::Json => render_json(out, classes, options),
     }
-    println!();
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Parser, EnumIter)]
-pub enum Feature {
-    Import,
-    ComponentScan,
-    Autowired,
-    Bean,
-    ConstructorInjection,
-    CombineImplAndInterface,
+/// Writes each of the names on a line of its own.
+fn write_lines<'a>(
+    out: &mut impl Write,
+    names: impl IntoIterator<Item = &'a String>,
+) -> io::Result<()> {
+    for name in names {
+        writeln!(out, "{}", name)?;
+    }
+    Ok(())
 }
 
-impl Display for Feature {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let debug = format!("{:?}", self);
-        write!(f, "{}", debug.to_lowercase())
+/// Graphviz program used to render images.
+const DOT_PROGRAM: &str = "dot";
+
+/// Renders DOT source into an image at `path` by piping it through Graphviz.
+fn render_image(program: &str, dot: &[u8], format: &ImageFormat, path: &Path) -> io::Result<()> {
+    let mut child = process::Command::new(program)
+        .arg(format!("-T{}", format))
+        .arg("-o")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => io::Error::new(
+                e.kind(),
+                format!(
+                    "could not find the `{}` program, install Graphviz to use --render",
+                    program
+                ),
+            ),
+            _ => e,
+        })?;
+    // Dropping stdin closes it, so that Graphviz knows the input is complete
+    child.stdin.take().expect("stdin is piped").write_all(dot)?;
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("{} failed: {}", program, status)));
     }
+    Ok(())
 }
 
-impl FromStr for Feature {
-    type Err = String;
-
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        for feature in Feature::iter() {
-            if input == feature.to_string() {
-                return Ok(feature);
-            }
+/// Writes the graph to a file, as an image if `image` is set.
+fn write_file(
+    path: &Path,
+    classes: &[Class],
+    options: &RenderOptions,
+    format: &Format,
+    image: Option<&ImageFormat>,
+    baseline: Option<&Graph>,
+) -> io::Result<()> {
+    match image {
+        Some(image) => {
+            let mut dot = Vec::new();
+            write_graph(&mut dot, classes, options, &Format::Dot, baseline)?;
+            render_image(DOT_PROGRAM, &dot, image, path)
+        }
+        None => {
+            let mut file = BufWriter::new(File::create(path)?);
+            write_graph(&mut file, classes, options, format, baseline)?;
+            file.flush()
         }
-        Err(format!("unknown feature {}", input))
     }
 }
 
-#[derive(Debug, Clone, Parser)]
-pub struct Features {
-    features: Vec<Feature>,
+/// Why the output could not be generated.
+#[derive(Debug)]
+enum GenerateError {
+    /// A file named on the command line, such as a palette or baseline, could not be used.
+    Input(String),
+    /// The output could not be written.
+    Output(io::Error),
 }
 
-impl Features {
-    pub fn contains(&self, feature: &Feature) -> bool {
-        self.features.contains(feature)
+impl GenerateError {
+    fn exit(&self) -> Exit {
+        match self {
+            GenerateError::Input(_) => Exit::Usage,
+            GenerateError::Output(_) => Exit::Output,
+        }
     }
 }
 
-impl Default for Features {
-    fn default() -> Self {
-        Self {
-            features: Feature::iter().collect(),
+impl Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerateError::Input(e) => write!(f, "{}", e),
+            GenerateError::Output(e) => write!(f, "Failed to write output: {}", e),
         }
     }
 }
 
-impl Display for Features {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let comma_separated_features = self.features.iter().map(|f| f.to_string()).join(",");
-        write!(f, "{}", comma_separated_features)
+impl From<io::Error> for GenerateError {
+    fn from(e: io::Error) -> Self {
+        GenerateError::Output(e)
     }
 }
 
-impl FromStr for Features {
-    type Err = String;
+/// How long to wait for further file changes before regenerating in watch mode.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let features = s
-            .split(',')
-            .map(|s| s.trim())
-            .map(FromStr::from_str)
-            .collect::<Result<Vec<Feature>, _>>()?;
-        Ok(Self { features })
-    }
+fn default_features() -> Features {
+    Feature::iter()
+        .filter(|f| f != &Feature::ComponentScan && f != &Feature::Entity)
+        .collect()
 }
 
-fn default_features() -> Features {
-    Features {
-        features: Feature::iter()
-            .filter(|f| f != &Feature::ComponentScan)
-            .collect(),
-    }
+/// Exit status of the program, so that scripts can tell failures apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    Success = 0,
+    /// Some files could not be read or parsed, the output is incomplete.
+    ParseFailures = 1,
+    /// Invalid command line arguments, also used by clap for its own errors, or files
+    /// named by them that cannot be used.
+    Usage = 2,
+    /// The output could not be written.
+    Output = 3,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Parser)]
-enum Direction {
-    /// Left to right.
-    LeftToRight,
-    /// Top to bottom.
-    TopToBottom,
+const EXIT_STATUS_HELP: &str = "Exit status:
+  0  Success
+  1  Some files could not be read or parsed
+  2  Invalid command line usage
+  3  The output could not be written";
+
+#[derive(Debug, Clone, Parser)]
+#[clap(after_help = EXIT_STATUS_HELP)]
+pub struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+    /// Directory to search, the current directory by default.
+    path: Option<String>,
+    /// Only include files of packages whose path contains this substring, e.g. com.example.demo.
+    #[clap(long, global = true, value_name = "SUBSTR")]
+    package: Option<String>,
+    /// Kinds of relations to include, e.g. `all`, `none` or `all,-import`.
+    #[clap(short, long, global = true, default_value_t = default_features())]
+    features: Features,
+    /// Treat classes with annotation NAME as components of TYPE, e.g. MyService=service. Can be repeated.
+    #[clap(long, global = true, value_name = "NAME=TYPE", value_parser = parse_alias)]
+    alias: Vec<(String, ComponentType)>,
+    /// Also inject the type wrapped in NAME<Foo> as Foo, like Provider and Optional. Can be repeated.
+    #[clap(long, global = true, value_name = "NAME")]
+    wrapper_type: Vec<String>,
+    /// Treat annotated classes without a known stereotype as components, to see them before adding aliases.
+    #[clap(long, global = true)]
+    assume_component: bool,
+    /// What to do with files that cannot be parsed: ignore, warn, or fail at the first one.
+    #[clap(long, global = true, value_name = "POLICY", default_value_t = ParseErrorPolicy::Warn)]
+    on_parse_error: ParseErrorPolicy,
+    /// Print how many files were parsed or skipped to stderr.
+    #[clap(long, global = true)]
+    stats: bool,
+    /// Print the time spent walking directories, reading, parsing and rendering to stderr.
+    #[clap(long, global = true)]
+    timing: bool,
+    /// Skip files whose path contains this substring. Can be repeated.
+    #[clap(long, global = true, value_name = "SUBSTR")]
+    exclude: Vec<String>,
+    /// Also search test sources under `src/test/`, which are skipped by default.
+    #[clap(long, global = true)]
+    include_tests: bool,
+    /// Maximum directory depth to search.
+    #[clap(long, global = true, value_name = "N")]
+    max_depth: Option<usize>,
+    /// Also search files excluded by .gitignore and .ignore files, such as generated sources.
+    #[clap(long, global = true)]
+    no_ignore: bool,
+    /// Parse the files listed one per line in FILE instead of searching the directory.
+    /// Relative paths are resolved against the working directory, and missing files
+    /// are reported as read failures.
+    #[clap(long, global = true, value_name = "FILE")]
+    files_from: Option<PathBuf>,
+    /// Same as the list-features command.
+    #[clap(long, hide = true)]
+    list_features: bool,
+    /// Same as the dump command.
+    #[clap(long, hide = true, value_name = "FILE")]
+    dump: Option<PathBuf>,
+    /// Options of the graph drawn when no command is given.
+    #[clap(flatten)]
+    graph: GraphArgs,
 }
 
-impl ToString for Direction {
-    fn to_string(&self) -> String {
-        match self {
-            Direction::LeftToRight => "LR".to_string(),
-            Direction::TopToBottom => "TB".to_string(),
-        }
-    }
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// Draw the graph, the default when no command is given.
+    Graph {
+        /// Directory to search, the current directory by default.
+        path: Option<String>,
+        #[clap(flatten)]
+        graph: Box<GraphArgs>,
+    },
+    /// Parse the sources and report the files that could not be parsed, without drawing.
+    Check {
+        /// Directory to search, the current directory by default.
+        path: Option<String>,
+    },
+    /// Parse only this file and print the parsed class.
+    Dump { file: PathBuf },
+    /// List the available features and the annotations they use.
+    ListFeatures,
 }
 
-impl FromStr for Direction {
-    type Err = String;
+impl Args {
+    /// Parses the command line. Options of the graph given before a command would be
+    /// ignored, as would a second path, so they are rejected.
+    fn try_parse_args<I, T>(args: I) -> Result<Args, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let mut command = <Args as CommandFactory>::command();
+        let matches = command.try_get_matches_from_mut(args)?;
+        if let Some((name, sub_matches)) = matches.subcommand() {
+            let given = |matches: &ArgMatches, id: &str| {
+                matches.try_get_raw(id).is_ok_and(|raw| raw.is_some())
+                    && matches.value_source(id) == Some(ValueSource::CommandLine)
+            };
+            let graph = GraphArgs::augment_args(clap::Command::new("graph"));
+            if let Some(arg) = graph
+                .get_arguments()
+                .find(|a| given(&matches, a.get_id().as_str()))
+            {
+                let message = format!(
+                    "the graph option '--{}' must come after the command, as in `graph --{}`",
+                    arg.get_long().unwrap_or_default(),
+                    arg.get_long().unwrap_or_default(),
+                );
+                return Err(command.error(ErrorKind::ArgumentConflict, message));
+            }
+            if given(&matches, "path") && given(sub_matches, "path") {
+                let message = format!("the path is given both before and after '{}'", name);
+                return Err(command.error(ErrorKind::ArgumentConflict, message));
+            }
+        }
+        Args::from_arg_matches(&matches)
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "LR" => Ok(Direction::LeftToRight),
-            "RB" => Ok(Direction::TopToBottom),
-            _ => Err(format!("unknown direction {}", s)),
+    /// The command to run, drawing the graph with the top level options if none is given.
+    fn command(&self) -> Command {
+        match (&self.command, &self.dump) {
+            (Some(command), _) => command.clone(),
+            _ if self.list_features => Command::ListFeatures,
+            (None, Some(file)) => Command::Dump { file: file.clone() },
+            (None, None) => Command::Graph {
+                path: self.path.clone(),
+                graph: Box::new(self.graph.clone()),
+            },
         }
     }
+
+    /// The directory to search, given either before or after the command.
+    fn path(&self) -> Option<&str> {
+        let command_path = match &self.command {
+            Some(Command::Graph { path, .. } | Command::Check { path }) => path.as_deref(),
+            _ => None,
+        };
+        command_path.or(self.path.as_deref())
+    }
 }
 
-#[derive(Debug, Clone, Parser)]
-pub struct Args {
-    /// Directory filter.
-    path: String,
-    /// Kinds of relations to include.
-    #[clap(short, long, default_value_t = default_features())]
-    features: Features,
+#[derive(Debug, Clone, clap::Args)]
+#[clap(group(ArgGroup::new("destination").args(["output", "split_by_type"]).multiple(true)))]
+struct GraphArgs {
     /// Direction of the graph (left to right or top to bottom).
     #[clap(short, long, default_value_t = Direction::LeftToRight)]
     direction: Direction,
+    /// Names in node labels: simple, such as Foo, or qualified, such as com.example.Foo.
+    #[clap(long, value_name = "STYLE", default_value_t = LabelStyle::Simple)]
+    label_style: LabelStyle,
+    /// Output format: dot, html, csv-matrix or json.
+    #[clap(long, default_value_t = Format::Dot)]
+    format: Format,
+    /// Draw what changed since the graph in this file, written earlier with `--format json`.
+    /// Added nodes and edges are green and removed ones are dashed red.
+    #[clap(
+        long,
+        value_name = "BASELINE_JSON",
+        conflicts_with_all = ["format", "split_by_type", "aggregate_by_package"]
+    )]
+    compare: Option<PathBuf>,
+    /// Render the graph into an image with Graphviz: svg or png. Needs `--output` or `--split-by-type`.
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        conflicts_with = "format",
+        requires = "destination"
+    )]
+    render: Option<ImageFormat>,
+    /// Print the classes that inject this class instead of the graph, one per line.
+    #[clap(
+        long,
+        value_name = "CLASS_NAME",
+        conflicts_with_all = ["format", "render", "compare", "split_by_type"]
+    )]
+    rdeps: Option<String>,
+    /// Include indirect dependents in the reverse dependency output.
+    #[clap(long, requires = "rdeps")]
+    rdeps_transitive: bool,
+    /// Leave out the legend.
+    #[clap(long)]
+    no_legend: bool,
+    /// Title of the graph, defaults to the path.
+    #[clap(long, value_name = "STR", conflicts_with = "no_title")]
+    title: Option<String>,
+    /// Leave out the graph title.
+    #[clap(long)]
+    no_title: bool,
+    /// Add the current time to the default title.
+    #[clap(long, conflicts_with_all = ["title", "no_title", "deterministic"])]
+    title_timestamp: bool,
+    /// Always produce the same output for the same sources, with layout hints that keep nodes in place.
+    #[clap(long)]
+    deterministic: bool,
+    /// Place @SpringBootApplication classes first and repositories without dependencies last.
+    #[clap(long)]
+    rank_entrypoints: bool,
+    /// Truncate edge labels longer than N characters.
+    #[clap(long, value_name = "N")]
+    edge_label_max_len: Option<usize>,
+    /// Draw one node per package, with edges counting the dependencies between packages.
+    #[clap(long)]
+    aggregate_by_package: bool,
+    /// Only draw components of these types, e.g. service,controller or restcontroller.
+    /// Controllers include rest controllers.
+    #[clap(long, value_name = "LIST", value_delimiter = ',')]
+    only_types: Vec<ComponentType>,
+    /// Use colors that stay distinguishable for people with color vision deficiencies.
+    #[clap(long)]
+    colorblind: bool,
+    /// Read colors replacing those of the palette from this file, with lines such as
+    /// `node.Service = #1f77b4` or `edge.Autowired = blue`.
+    #[clap(long, value_name = "PATH")]
+    palette_file: Option<PathBuf>,
+    /// Background and text colors: light, or dark for dark-mode documentation.
+    #[clap(long, value_name = "THEME", default_value_t = Theme::Light)]
+    theme: Theme,
+    /// Show the injected field or parameter name on dependency edges.
+    #[clap(long)]
+    show_names: bool,
+    /// Draw imports of classes that were not parsed as edges to their package.
+    #[clap(long)]
+    group_external_imports: bool,
+    /// Infer the concrete type of @Bean methods from `return new Foo(..)` or `Foo.builder()..build()`.
+    /// This is a best-effort guess from the first return statement of the method.
+    /// Groovy `def` methods have no other type, so theirs is inferred even without this flag.
+    #[clap(long)]
+    infer_impls: bool,
+    /// Draw a single edge between two nodes joined more than once, such as a configuration
+    /// both importing and injecting another, labelled with all the ways they are.
+    /// CSV matrices and JSON then count each pair once.
+    #[clap(long)]
+    merge_parallel_edges: bool,
+    /// Write the graph to this file instead of standard output.
+    #[clap(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Regenerate the output whenever a Java or Groovy file changes.
+    #[clap(long, requires = "output")]
+    watch: bool,
+    /// Write one graph per component type into this directory instead of printing a graph,
+    /// such as `services.dot`. Rest controllers are in both `restcontrollers.dot` and `controllers.dot`.
+    #[clap(long, value_name = "DIR")]
+    split_by_type: Option<PathBuf>,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    tracing_subscriber::fmt()
-        .with_writer(std::io::stderr)
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
+/// Directory of test sources in Maven and Gradle projects.
+const TEST_SOURCES: &str = "src/test/";
+
+/// Path substrings to skip, test sources among them unless they are included.
+fn excludes(args: &Args) -> Vec<String> {
+    let mut excludes = args.exclude.clone();
+    if !args.include_tests {
+        excludes.push(TEST_SOURCES.to_string());
+    }
+    excludes
+}
 
-    let args = Args::parse();
+/// Builds the options for finding Java files under the searched directory.
+///
+/// A path that is not a directory is used as a package filter instead, as it was before
+/// `--package` existed.
+fn walk_options(args: &Args) -> Result<WalkOptions, String> {
+    let package = args.package.as_deref().map(|p| p.replace('.', "/"));
+    let (root, package) = match (args.path(), package) {
+        (None, package) => (PathBuf::from("./"), package.unwrap_or_default()),
+        (Some(path), package) if Path::new(path).is_dir() => {
+            (PathBuf::from(path), package.unwrap_or_default())
+        }
+        (Some(path), None) => {
+            tracing::warn!(
+                "{} is not a directory, filtering by it as a package is deprecated, use --package",
+                path
+            );
+            (PathBuf::from("./"), path.to_string())
+        }
+        (Some(path), Some(_)) => return Err(format!("{} is not a directory", path)),
+    };
+    let files = match &args.files_from {
+        Some(manifest) => {
+            let content = fs::read_to_string(manifest)
+                .map_err(|e| format!("Failed to read {}: {}", manifest.display(), e))?;
+            let files = content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from)
+                .collect();
+            Some(files)
+        }
+        None => None,
+    };
+    WalkOptionsBuilder::default()
+        .root(root)
+        .package(package)
+        .exclude(excludes(args))
+        .max_depth(args.max_depth)
+        .no_ignore(args.no_ignore)
+        .files(files)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Builds the options telling the parser about annotations and types it cannot know of.
+fn parse_options(args: &Args) -> Result<ParseOptions, String> {
+    let mut wrapper_types: Vec<String> = WRAPPER_TYPES.iter().map(|w| w.to_string()).collect();
+    wrapper_types.extend(args.wrapper_type.iter().cloned());
+    ParseOptionsBuilder::default()
+        .aliases(args.alias.iter().cloned().collect())
+        .wrapper_types(wrapper_types)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Finds the 1-based line and column where `remaining`, a slice of `source`, starts.
+fn line_column(source: &str, remaining: &str) -> (usize, usize) {
+    let offset = (remaining.as_ptr() as usize)
+        .checked_sub(source.as_ptr() as usize)
+        .filter(|offset| offset + remaining.len() <= source.len())
+        .unwrap_or(0);
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |pos| pos + 1) + 1;
+    (line, column)
+}
 
-    println!("digraph Components {{");
-    println!("    rankdir={};", args.direction.to_string());
+/// Parses a single file and writes the resulting class, to see how the file is understood.
+fn dump(
+    out: &mut impl Write,
+    path: &Path,
+    parse_options: &ParseOptions,
+) -> Result<(), Box<dyn Error>> {
+    let content = read_file(path)?;
+    let class = match parse_class_with_options(&content, parse_options) {
+        Ok((_, class)) => class,
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            let (line, column) = line_column(&content, e.input);
+            let message = format!("line {}, column {}: {:?}", line, column, e.code);
+            return Err(message.into());
+        }
+        Err(nom::Err::Incomplete(_)) => return Err("incomplete input".into()),
+    };
+    writeln!(out, "{:#?}", class)?;
+    Ok(())
+}
 
-    print_legend();
+/// Custom annotations declared among the sources, which other classes are annotated with.
+#[derive(Debug, Default)]
+struct CustomAnnotations {
+    /// Annotations meta-annotated with a stereotype, such as
+    /// `@RestController public @interface RestEndpoint`, so classes using them become components.
+    stereotypes: HashMap<String, ComponentType>,
+    /// Annotations meta-annotated with `@Qualifier`, which qualify injected fields.
+    qualifiers: HashSet<String>,
+}
+
+impl CustomAnnotations {
+    /// Looks through the annotation declarations for stereotypes and qualifiers.
+    fn from_declarations<'a>(declarations: impl Iterator<Item = &'a Class>) -> Self {
+        let mut found = CustomAnnotations::default();
+        for class in declarations {
+            if class.annotations().iter().any(|a| a == "Qualifier") {
+                tracing::debug!("Found qualifier @{}", class.name());
+                found.qualifiers.insert(class.name().to_string());
+            }
+            if let Some(component_type) = class.component_type() {
+                tracing::debug!("Found stereotype @{} ({:?})", class.name(), component_type);
+                found
+                    .stereotypes
+                    .insert(class.name().to_string(), component_type.clone());
+            }
+        }
+        found
+    }
+}
 
-    let classes: Vec<Class> = javafiles(&args.path)
-        .filter_map(|entry| {
-            let file_name = entry.file_name();
+/// Parses the Java files found by the walk, skipping those that cannot be read or parsed.
+///
+/// The annotation declarations among them are then looked through, so that composed
+/// stereotypes and custom qualifiers are recognized. Aliases given on the command line
+/// take precedence over the stereotypes found.
+fn parse_classes(
+    walk_options: &WalkOptions,
+    parse_options: &ParseOptions,
+    print_stats: bool,
+    assume_component: bool,
+    on_parse_error: ParseErrorPolicy,
+    timings: &mut Timings,
+) -> (Vec<Class>, ParseStats) {
+    let mut stats = ParseStats::default();
+    let paths: Vec<_> = timings.time(Phase::Walk, || source_files(walk_options).collect());
+    let mut stopped = false;
+    let classes: Vec<Class> = paths
+        .iter()
+        .filter_map(|path| {
+            // Failing on parse errors leaves the files after the first one unread
+            if stopped {
+                return None;
+            }
+            let file_name = path.file_name().unwrap_or_default();
             tracing::debug!("Reading file {:?}", file_name);
-            let content = read_file(entry.path()).ok().or_else(|| {
-                tracing::warn!("Failed to read file {:?}", file_name);
-                None
-            })?;
-            let (_, class) = parse_class(&content).ok().or_else(|| {
-                tracing::warn!("Failed to parse file {:?}", file_name);
-                None
-            })?;
+            let content = match timings.time(Phase::Read, || read_file(path)) {
+                Ok(content) => content,
+                Err(e) => {
+                    tracing::warn!("Failed to read file {}: {}", path.display(), e);
+                    stats.record_skipped(SkipReason::Read(e.to_string()));
+                    return None;
+                }
+            };
+            let class = match timings.time(Phase::Parse, || {
+                parse_class_with_options(&content, parse_options)
+            }) {
+                Ok((_, class)) => class,
+                Err(e) => {
+                    let (message, reason) = match e {
+                        nom::Err::Error(e) | nom::Err::Failure(e) => {
+                            let (line, column) = line_column(&content, e.input);
+                            let message = format!(
+                                "Failed to parse {:?} at line {}, column {}: {:?}",
+                                file_name, line, column, e.code
+                            );
+                            (message, e.code.description().to_string())
+                        }
+                        nom::Err::Incomplete(_) => (
+                            format!("Failed to parse {:?}: incomplete input", file_name),
+                            "incomplete input".to_string(),
+                        ),
+                    };
+                    match on_parse_error {
+                        ParseErrorPolicy::Ignore => {}
+                        ParseErrorPolicy::Warn => tracing::warn!("{}", message),
+                        ParseErrorPolicy::Fail => {
+                            tracing::error!("{}", message);
+                            stopped = true;
+                        }
+                    }
+                    stats.record_skipped(SkipReason::Parse(reason));
+                    return None;
+                }
+            };
+            stats.record_parsed();
             Some(class)
         })
         .collect();
+    let CustomAnnotations {
+        stereotypes,
+        qualifiers,
+    } = CustomAnnotations::from_declarations(classes.iter().filter(|c| c.annotation_declaration()));
+    let mut classes: Vec<Class> = classes
+        .into_iter()
+        // Stereotype annotations are not components themselves
+        .filter(|c| !c.annotation_declaration())
+        .map(|class| {
+            let class = class
+                .with_stereotypes(&stereotypes)
+                .with_qualifier_annotations(&qualifiers);
+            if assume_component {
+                class.assume_component()
+            } else {
+                class
+            }
+        })
+        .collect();
+    if print_stats {
+        eprintln!("{}", stats);
+    }
+    sort_classes(&mut classes);
+    (classes, stats)
+}
 
-    for class in &classes {
-        let name = if args.features.contains(&Feature::CombineImplAndInterface) {
-            class
-                .interfaces()
-                .first()
-                .map(|s| s.to_string())
-                .unwrap_or(class.name().to_string())
-        } else {
-            class.name().to_string()
-        };
+/// Runs the whole pipeline once: finds and parses the classes and writes the requested output.
+fn generate(
+    args: &Args,
+    graph: &GraphArgs,
+    walk_options: &WalkOptions,
+    parse_options: &ParseOptions,
+) -> Result<ParseStats, GenerateError> {
+    let mut timings = Timings::default();
+    let result = generate_timed(args, graph, walk_options, parse_options, &mut timings);
+    if args.timing {
+        eprintln!("{}", timings);
+    }
+    result
+}
 
-        // Node itself
-        if let Some(component_type) = class.component_type() {
-            println!(
-                "    {} [fillcolor=\"{}\"style=filled];",
-                name,
-                component_type.color_code()
-            )
-        } else {
-            tracing::trace!("Skipping class without component type: {}", name);
-            continue;
+/// Runs the pipeline, adding the time spent in each phase to `timings`.
+fn generate_timed(
+    args: &Args,
+    graph: &GraphArgs,
+    walk_options: &WalkOptions,
+    parse_options: &ParseOptions,
+    timings: &mut Timings,
+) -> Result<ParseStats, GenerateError> {
+    let (classes, stats) = parse_classes(
+        walk_options,
+        parse_options,
+        args.stats,
+        args.assume_component,
+        args.on_parse_error,
+        timings,
+    );
+    if args.on_parse_error == ParseErrorPolicy::Fail && stats.parse_errors() > 0 {
+        return Ok(stats);
+    }
+    let baseline = match &graph.compare {
+        Some(path) => {
+            let file = File::open(path).map_err(|e| {
+                GenerateError::Input(format!("Failed to open {}: {}", path.display(), e))
+            })?;
+            let baseline = read_graph(io::BufReader::new(file)).map_err(|e| {
+                GenerateError::Input(format!("Failed to read {}: {}", path.display(), e))
+            })?;
+            Some(baseline)
         }
+        None => None,
+    };
 
-        // Imports
-        if args.features.contains(&Feature::Import) {
-            tracing::trace!("{}: Imports {:?}", name, class.imports());
-            for import in class.imports() {
-                tracing::trace!("Import here");
-                println!("    {} -> {} [label=\"@Import\"];", name, import);
+    // Reverse dependencies
+    if let Some(name) = &graph.rdeps {
+        let dependents = Graph::new(&classes).reverse_dependencies(name, graph.rdeps_transitive);
+        match &graph.output {
+            Some(path) => {
+                let mut file = BufWriter::new(File::create(path)?);
+                write_lines(&mut file, &dependents)?;
+                file.flush()?;
             }
+            None => write_lines(&mut io::stdout().lock(), &dependents)?,
         }
+        return Ok(stats);
+    }
 
-        // Component scans
-        if args.features.contains(&Feature::ComponentScan) {
-            for package in class.component_scans() {
-                println!("    \"{}\" [style=filled];", package);
-                println!(
-                    "    \"{}\" -> \"{}\" [label=\"@ComponentScan\"];",
-                    name, package
-                );
-                let scanned = classes
-                    .iter()
-                    .filter(|c| c.package().contains(package) && c.component_type().is_some());
-                for c in scanned {
-                    println!("    \"{}\" -> {} [label=contains];", package, c.name());
-                }
+    let title = if graph.no_title {
+        None
+    } else {
+        Some(graph.title.clone().unwrap_or_else(|| {
+            let path = args.path().unwrap_or_default();
+            if graph.title_timestamp {
+                format!(
+                    "spring-visualizer: {} @ {}",
+                    path,
+                    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+                )
+            } else {
+                format!("spring-visualizer: {}", path)
             }
-        }
+        }))
+    };
+    let color_overrides = match &graph.palette_file {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| s.parse::<ColorOverrides>())
+            .map_err(|e| {
+                GenerateError::Input(format!(
+                    "Failed to read palette file {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?,
+        None => ColorOverrides::default(),
+    };
+    let options = RenderOptionsBuilder::default()
+        .features(args.features.clone())
+        .direction(graph.direction.clone())
+        .legend(!graph.no_legend)
+        .show_names(graph.show_names)
+        .group_external_imports(graph.group_external_imports)
+        .theme(graph.theme)
+        .merge_parallel_edges(graph.merge_parallel_edges)
+        .edge_label_max_len(graph.edge_label_max_len)
+        .aggregate_by_package(graph.aggregate_by_package)
+        .title(title)
+        .only_types(graph.only_types.clone())
+        .deterministic(graph.deterministic)
+        .rank_entrypoints(graph.rank_entrypoints)
+        .label_style(graph.label_style.clone())
+        .source_path(args.path().map(String::from))
+        .palette(if graph.colorblind {
+            Palette::Colorblind
+        } else {
+            Palette::Default
+        })
+        .color_overrides(color_overrides)
+        .build()
+        .map_err(|e| GenerateError::Input(e.to_string()))?;
 
-        // Interface implementations
-        if !args.features.contains(&Feature::CombineImplAndInterface) {
-            for interface in class.interfaces() {
-                println!("    {} -> {} [label=impl];", interface, name);
+    // One graph per component type
+    if let Some(dir) = &graph.split_by_type {
+        fs::create_dir_all(dir)?;
+        for component_type in ComponentType::iter() {
+            // Rest controllers are with the controllers as well
+            let of_type: Vec<Class> = classes
+                .iter()
+                .filter(|c| c.component_type().is_some_and(|t| t.is_a(&component_type)))
+                .cloned()
+                .collect();
+            if of_type.is_empty() {
+                continue;
             }
+            let extension = match &graph.render {
+                Some(image) => image.extension(),
+                None => graph.format.extension(),
+            };
+            let path = dir.join(format!("{}.{}", component_type.plural(), extension));
+            timings.time(Phase::Render, || {
+                write_file(
+                    &path,
+                    &of_type,
+                    &options,
+                    &graph.format,
+                    graph.render.as_ref(),
+                    None,
+                )
+            })?;
+            eprintln!("Wrote {}", path.display());
         }
+        return Ok(stats);
+    }
+
+    timings.time(Phase::Render, || match &graph.output {
+        Some(path) => write_file(
+            path,
+            &classes,
+            &options,
+            &graph.format,
+            graph.render.as_ref(),
+            baseline.as_ref(),
+        ),
+        None => write_graph(
+            &mut std::io::stdout().lock(),
+            &classes,
+            &options,
+            &graph.format,
+            baseline.as_ref(),
+        ),
+    })?;
+
+    Ok(stats)
+}
 
-        // Constructor injection
-        if args.features.contains(&Feature::ConstructorInjection) {
-            for param in class.parameters() {
-                println!("    {} -> {} [label=\"Constructor\"];", name, param.class);
+/// What the watch loop is woken up by.
+enum WatchEvent {
+    Changed,
+    Stop,
+}
+
+/// Regenerates the output whenever a Java or Groovy file changes, until interrupted with Ctrl-C.
+fn watch(
+    args: &Args,
+    graph: &GraphArgs,
+    walk_options: &WalkOptions,
+    parse_options: &ParseOptions,
+) -> Result<(), Box<dyn Error>> {
+    let (sender, receiver) = mpsc::channel();
+    let stop = sender.clone();
+    ctrlc::set_handler(move || {
+        let _ = stop.send(WatchEvent::Stop);
+    })?;
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if event.paths.iter().any(|p| is_source_file(p)) => {
+                let _ = sender.send(WatchEvent::Changed);
             }
-        }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Watch error: {}", e),
+        })?;
+    watcher.watch(walk_options.root(), RecursiveMode::Recursive)?;
+    eprintln!("Watching for changes, press Ctrl-C to stop");
 
-        // Autowires
-        if args.features.contains(&Feature::Autowired) {
-            for autowire in class.autowires() {
-                println!(
-                    "    {} -> {} [label=\"@Autowired\"];",
-                    name,
-                    autowire.class()
-                );
+    while let Ok(WatchEvent::Changed) = receiver.recv() {
+        // Saving a file often produces several events, so wait for them to settle
+        loop {
+            match receiver.recv_timeout(WATCH_DEBOUNCE) {
+                Ok(WatchEvent::Changed) => continue,
+                Ok(WatchEvent::Stop) => return Ok(()),
+                Err(_) => break,
             }
         }
+        tracing::info!("Regenerating after changes");
+        if let Err(e) = generate(args, graph, walk_options, parse_options) {
+            tracing::error!("Failed to regenerate: {}", e);
+        }
+    }
+    Ok(())
+}
 
-        // Beans
-        if args.features.contains(&Feature::Bean) {
-            for bean in class.bean_defs() {
-                println!("    {} [fillcolor=\"#6b1d1d\",style=filled];", bean.class());
-                println!("    {} -> {} [label=\"@Bean\"];", name, bean.class());
-                // Print bean parameters
-                if args.features.contains(&Feature::ConstructorInjection) {
-                    for param in bean.parameters().iter() {
-                        println!(
-                            "    {} -> {} [label=\"@Autowired (CI)\"];",
-                            bean.class(),
-                            param.class
-                        );
-                    }
-                }
+/// Generates the output, then keeps regenerating it in watch mode, and tells how it went.
+fn run(
+    args: &Args,
+    graph: &GraphArgs,
+    walk_options: &WalkOptions,
+    parse_options: &ParseOptions,
+) -> Exit {
+    let stats = match generate(args, graph, walk_options, parse_options) {
+        Ok(stats) => stats,
+        Err(e) => {
+            tracing::error!("{}", e);
+            return e.exit();
+        }
+    };
+    if graph.watch {
+        if let Err(e) = watch(args, graph, walk_options, parse_options) {
+            tracing::error!("Failed to watch for changes: {}", e);
+            return Exit::Output;
+        }
+    }
+    parse_exit(&stats, args.on_parse_error)
+}
+
+/// Parses the sources without drawing anything, telling whether every file could be parsed.
+fn check(args: &Args, walk_options: &WalkOptions, parse_options: &ParseOptions) -> Exit {
+    let mut timings = Timings::default();
+    let (_, stats) = parse_classes(
+        walk_options,
+        parse_options,
+        true,
+        args.assume_component,
+        args.on_parse_error,
+        &mut timings,
+    );
+    if args.timing {
+        eprintln!("{}", timings);
+    }
+    parse_exit(&stats, args.on_parse_error)
+}
+
+/// The exit status for the files that were matched, where ignored parse errors do not count.
+fn parse_exit(stats: &ParseStats, on_parse_error: ParseErrorPolicy) -> Exit {
+    let failures = match on_parse_error {
+        ParseErrorPolicy::Ignore => stats.skipped() - stats.parse_errors(),
+        ParseErrorPolicy::Warn | ParseErrorPolicy::Fail => stats.skipped(),
+    };
+    if failures > 0 {
+        Exit::ParseFailures
+    } else {
+        Exit::Success
+    }
+}
+
+fn main() {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    // Exits with status 2 on invalid arguments
+    let args = Args::try_parse_args(std::env::args_os()).unwrap_or_else(|e| e.exit());
+    let parse_options = match parse_options(&args) {
+        Ok(parse_options) => parse_options,
+        Err(e) => {
+            tracing::error!("Invalid options: {}", e);
+            process::exit(Exit::Usage as i32);
+        }
+    };
+
+    let command = args.command();
+    match &command {
+        Command::ListFeatures => {
+            print_features();
+            process::exit(Exit::Success as i32);
+        }
+        Command::Dump { file } => {
+            if let Err(e) = dump(&mut io::stdout().lock(), file, &parse_options) {
+                tracing::error!("Failed to parse {}: {}", file.display(), e);
+                process::exit(Exit::ParseFailures as i32);
             }
+            process::exit(Exit::Success as i32);
+        }
+        Command::Graph { .. } | Command::Check { .. } => {}
+    }
+
+    let walk_options = match walk_options(&args) {
+        Ok(walk_options) => walk_options,
+        Err(e) => {
+            tracing::error!("Invalid options: {}", e);
+            process::exit(Exit::Usage as i32);
+        }
+    };
+    let exit = match &command {
+        Command::Check { .. } => check(&args, &walk_options, &parse_options),
+        Command::Graph { graph, .. } => {
+            let parse_options = parse_options.with_infer_implementations(graph.infer_impls);
+            run(&args, graph, &walk_options, &parse_options)
         }
+        Command::ListFeatures | Command::Dump { .. } => unreachable!("handled above"),
+    };
+    process::exit(exit as i32);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use clap::Parser;
+    use spring_visualizer::{
+        autowired::Qualifier,
+        class::ParseOptions,
+        component_type::ComponentType,
+        graph::Graph,
+        render::ImageFormat,
+        stats::{ParseErrorPolicy, Timings},
+        walk::{source_files, WalkOptionsBuilder},
+    };
+
+    use super::{
+        dump, excludes, line_column, parse_classes, render_image, run, walk_options, Args, Command,
+        Exit,
+    };
+
+    fn exit_status(root: &Path, output: &Path) -> Exit {
+        exit_status_with(root, output, &[])
     }
 
-    println!("}}");
+    fn exit_status_with(root: &Path, output: &Path, args: &[&str]) -> Exit {
+        let args = Args::parse_from(
+            [
+                &[
+                    "spring-visualizer",
+                    "",
+                    "--output",
+                    output.to_str().unwrap(),
+                ],
+                args,
+            ]
+            .concat(),
+        );
+        let walk_options = WalkOptionsBuilder::default()
+            .root(root.to_path_buf())
+            .build()
+            .unwrap();
+        run(&args, &args.graph, &walk_options, &ParseOptions::default())
+    }
 
-    Ok(())
+    #[test]
+    fn dump_prints_parsed_class() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Foo.java");
+        fs::write(
+            &path,
+            "package a;\n\n@Service\npublic class Foo {\n    @Autowired\n    private Bar bar;\n}\n",
+        )
+        .unwrap();
+        let mut out = Vec::new();
+        dump(&mut out, &path, &ParseOptions::default()).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("Class {"));
+        assert!(out.contains("name: \"Foo\""));
+        assert!(out.contains("class: \"Bar\""));
+
+        fs::write(&path, "package a;\n\nnot java\n").unwrap();
+        let error = dump(&mut Vec::new(), &path, &ParseOptions::default()).unwrap_err();
+        assert!(error.to_string().starts_with("line "));
+    }
+
+    #[test]
+    fn parse_error_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        let sources = dir.path().join("src");
+        fs::create_dir(&sources).unwrap();
+        fs::write(
+            sources.join("Foo.java"),
+            "package a;\n\n@Service\npublic class Foo {}\n",
+        )
+        .unwrap();
+        fs::write(
+            sources.join("Bar.java"),
+            "package a;\n\npublic clas Bar {}\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.dot");
+        let run = |policy: &str| {
+            let _ = fs::remove_file(&output);
+            let exit = exit_status_with(&sources, &output, &["--on-parse-error", policy]);
+            (exit, fs::read_to_string(&output).ok())
+        };
+
+        let (exit, out) = run("warn");
+        assert_eq!(Exit::ParseFailures, exit);
+        assert!(out.unwrap().contains("Foo [fillcolor="));
+
+        let (exit, out) = run("ignore");
+        assert_eq!(Exit::Success, exit);
+        assert!(out.unwrap().contains("Foo [fillcolor="));
+
+        let (exit, out) = run("fail");
+        assert_eq!(Exit::ParseFailures, exit);
+        assert_eq!(None, out);
+
+        assert!(Args::try_parse_from(["spring-visualizer", "", "--on-parse-error", "x"]).is_err());
+        assert_eq!(
+            ParseErrorPolicy::Warn,
+            Args::parse_from(["spring-visualizer", ""]).on_parse_error
+        );
+    }
+
+    #[test]
+    fn exit_status_reflects_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out.dot");
+        fs::write(
+            dir.path().join("Foo.java"),
+            "package a;\n\n@Service\npublic class Foo {}\n",
+        )
+        .unwrap();
+        assert_eq!(Exit::Success, exit_status(dir.path(), &output));
+
+        fs::write(
+            dir.path().join("Bar.java"),
+            "package a;\n\npublic clas Bar {}\n",
+        )
+        .unwrap();
+        assert_eq!(Exit::ParseFailures, exit_status(dir.path(), &output));
+
+        let unwritable = dir.path().join("missing").join("out.dot");
+        assert_eq!(Exit::Output, exit_status(dir.path(), &unwritable));
+    }
+
+    #[test]
+    fn rdeps_written_to_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let sources = dir.path().join("src");
+        fs::create_dir(&sources).unwrap();
+        fs::write(
+            sources.join("Foo.java"),
+            "package a;\n\n@Service\npublic class Foo {}\n",
+        )
+        .unwrap();
+        fs::write(
+            sources.join("Bar.java"),
+            "package a;\n\n@Service\npublic class Bar {\n    @Autowired\n    private Foo foo;\n}\n",
+        )
+        .unwrap();
+        let output = dir.path().join("rdeps.txt");
+        assert_eq!(
+            Exit::Success,
+            exit_status_with(&sources, &output, &["--rdeps", "Foo"])
+        );
+        assert_eq!("Bar\n", fs::read_to_string(&output).unwrap());
+        assert!(
+            Args::try_parse_from(["spring-visualizer", "--rdeps", "Foo", "--format", "json"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn compare_with_json_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let sources = dir.path().join("src");
+        fs::create_dir(&sources).unwrap();
+        fs::write(
+            sources.join("Foo.java"),
+            "package a;\n\n@Service\npublic class Foo {}\n",
+        )
+        .unwrap();
+        let baseline = dir.path().join("baseline.json");
+        assert_eq!(
+            Exit::Success,
+            exit_status_with(&sources, &baseline, &["--format", "json"])
+        );
+
+        fs::write(
+            sources.join("Bar.java"),
+            "package a;\n\n@Service\npublic class Bar {\n    @Autowired\n    private Foo foo;\n}\n",
+        )
+        .unwrap();
+        let output = dir.path().join("out.dot");
+        assert_eq!(
+            Exit::Success,
+            exit_status_with(
+                &sources,
+                &output,
+                &["--compare", baseline.to_str().unwrap()]
+            )
+        );
+        let out = fs::read_to_string(&output).unwrap();
+        assert!(out.contains("Bar [label=\"Bar\",fillcolor=\"#a81347\",style=filled,color=green"));
+        assert!(out.contains("Bar -> Foo [label=\"@Autowired\",color=green"));
+
+        let missing = dir.path().join("missing.json");
+        assert_eq!(
+            Exit::Usage,
+            exit_status_with(&sources, &output, &["--compare", missing.to_str().unwrap()])
+        );
+        let palette = dir.path().join("palette.txt");
+        fs::write(&palette, "node.unknown = red\n").unwrap();
+        assert_eq!(
+            Exit::Usage,
+            exit_status_with(
+                &sources,
+                &output,
+                &["--palette-file", palette.to_str().unwrap()]
+            )
+        );
+    }
+
+    #[test]
+    fn parse_classes_resolves_composed_stereotypes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("RestEndpoint.java"),
+            "package a;\n\n@RestController\npublic @interface RestEndpoint {}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("FooEndpoint.java"),
+            "package a;\n\n@RestEndpoint\npublic class FooEndpoint {}\n",
+        )
+        .unwrap();
+        let walk_options = WalkOptionsBuilder::default()
+            .root(dir.path().to_path_buf())
+            .build()
+            .unwrap();
+
+        let (classes, stats) = parse_classes(
+            &walk_options,
+            &ParseOptions::default(),
+            false,
+            false,
+            ParseErrorPolicy::Warn,
+            &mut Timings::default(),
+        );
+        assert_eq!(2, stats.parsed());
+        assert_eq!(1, classes.len());
+        assert_eq!("FooEndpoint", classes[0].name());
+        assert_eq!(
+            Some(&ComponentType::RestController),
+            classes[0].component_type()
+        );
+    }
+
+    #[test]
+    fn parse_classes_resolves_custom_qualifiers() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "PrimaryDb.java",
+                "package a;\n\n@Qualifier\n@Retention(RetentionPolicy.RUNTIME)\npublic @interface PrimaryDb {}\n",
+            ),
+            (
+                "MainStore.java",
+                "package a;\n\n@Repository\n@PrimaryDb\npublic class MainStore implements Store {}\n",
+            ),
+            (
+                "BackupStore.java",
+                "package a;\n\n@Repository\npublic class BackupStore implements Store {}\n",
+            ),
+            (
+                "FooService.java",
+                "package a;\n\n@Service\npublic class FooService {\n    @Autowired\n    @PrimaryDb\n    private Store store;\n}\n",
+            ),
+            (
+                "BarService.java",
+                "package a;\n\n@Service\npublic class BarService {\n    BarService(@PrimaryDb Store store) {}\n}\n",
+            ),
+        ];
+        for (name, content) in files {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        let walk_options = WalkOptionsBuilder::default()
+            .root(dir.path().to_path_buf())
+            .build()
+            .unwrap();
+
+        let (classes, _) = parse_classes(
+            &walk_options,
+            &ParseOptions::default(),
+            false,
+            false,
+            ParseErrorPolicy::Warn,
+            &mut Timings::default(),
+        );
+        let service = classes.iter().find(|c| c.name() == "FooService").unwrap();
+        let qualifier = Some(Qualifier::Annotation("PrimaryDb".to_string()));
+        assert_eq!(qualifier.as_ref(), service.autowires()[0].qualifier());
+        let service = classes.iter().find(|c| c.name() == "BarService").unwrap();
+        assert_eq!(qualifier.as_ref(), service.parameters()[0].qualifier());
+        let edges: Vec<_> = Graph::new(&classes)
+            .edges()
+            .iter()
+            .filter(|e| e.kind().is_injection())
+            .map(|e| (e.to().to_string(), e.ambiguous()))
+            .collect();
+        assert_eq!(vec![("MainStore".to_string(), false); 2], edges);
+    }
+
+    #[test]
+    fn render_image_reports_missing_graphviz() {
+        let dir = tempfile::tempdir().unwrap();
+        let error = render_image(
+            "spring-visualizer-no-such-dot",
+            b"digraph {}",
+            &ImageFormat::Svg,
+            &dir.path().join("out.svg"),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("install Graphviz"));
+    }
+
+    #[test]
+    fn parse_classes_assumes_components() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("Foo.java"),
+            "package a;\n\n@MyCustom\npublic class Foo {}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("Bar.java"),
+            "package a;\n\npublic class Bar {}\n",
+        )
+        .unwrap();
+        let walk_options = WalkOptionsBuilder::default()
+            .root(dir.path().to_path_buf())
+            .build()
+            .unwrap();
+
+        let (classes, _) = parse_classes(
+            &walk_options,
+            &ParseOptions::default(),
+            false,
+            false,
+            ParseErrorPolicy::Warn,
+            &mut Timings::default(),
+        );
+        assert!(classes.iter().all(|c| c.component_type().is_none()));
+
+        let (classes, _) = parse_classes(
+            &walk_options,
+            &ParseOptions::default(),
+            false,
+            true,
+            ParseErrorPolicy::Warn,
+            &mut Timings::default(),
+        );
+        let types: Vec<_> = classes
+            .iter()
+            .map(|c| (c.name(), c.component_type()))
+            .collect();
+        assert_eq!(
+            vec![("Bar", None), ("Foo", Some(&ComponentType::Component))],
+            types
+        );
+    }
+
+    #[test]
+    fn test_sources_are_excluded_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, source) in [
+            (
+                "src/main/java/a/Foo.java",
+                "package a;\n\n@Service\npublic class Foo {}\n",
+            ),
+            (
+                "src/test/java/a/FooTestConfig.java",
+                "package a;\n\n@TestConfiguration\npublic class FooTestConfig {}\n",
+            ),
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+        let types = |args: &[&str]| {
+            let args = Args::parse_from([&["spring-visualizer", ""], args].concat());
+            let walk_options = WalkOptionsBuilder::default()
+                .root(dir.path().to_path_buf())
+                .exclude(excludes(&args))
+                .build()
+                .unwrap();
+            let (classes, _) = parse_classes(
+                &walk_options,
+                &ParseOptions::default(),
+                false,
+                false,
+                ParseErrorPolicy::Warn,
+                &mut Timings::default(),
+            );
+            classes
+                .iter()
+                .map(|c| c.component_type().cloned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec![Some(ComponentType::Service)], types(&[]));
+        assert_eq!(
+            vec![
+                Some(ComponentType::Service),
+                Some(ComponentType::TestConfiguration)
+            ],
+            types(&["--include-tests"])
+        );
+    }
+
+    #[test]
+    fn path_and_package_filter_independently() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in [
+            "one/src/main/java/a/b/Foo.java",
+            "one/src/main/java/x/y/Bar.java",
+            "two/src/main/java/a/b/Baz.java",
+        ] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "package a;\n\n@Service\npublic class Foo {}\n").unwrap();
+        }
+        let files = |args: &[&str]| {
+            let args = Args::parse_from([&["spring-visualizer"], args].concat());
+            let mut files: Vec<_> = source_files(&walk_options(&args).unwrap())
+                .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            files.sort();
+            files
+        };
+        let one = dir.path().join("one");
+        let one = one.to_str().unwrap();
+
+        assert_eq!(vec!["Bar.java", "Foo.java"], files(&[one]));
+        assert_eq!(vec!["Foo.java"], files(&[one, "--package", "a.b"]));
+        assert_eq!(
+            vec!["Baz.java", "Foo.java"],
+            files(&[dir.path().to_str().unwrap(), "--package", "a.b"])
+        );
+    }
+
+    #[test]
+    fn files_from_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        for path in ["a/b/Foo.java", "a/b/Bar.java", "x/y/Baz.java"] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "package a;\n\n@Service\npublic class Foo {}\n").unwrap();
+        }
+        let manifest = dir.path().join("changed.txt");
+        let listed: Vec<_> = ["a/b/Foo.java", "x/y/Baz.java"]
+            .iter()
+            .map(|p| dir.path().join(p).to_string_lossy().to_string())
+            .collect();
+        fs::write(&manifest, format!("{}\n\n{}\n", listed[0], listed[1])).unwrap();
+        let files = |args: &[&str]| {
+            let manifest = ["--files-from", manifest.to_str().unwrap()];
+            let args = Args::parse_from([&["spring-visualizer"], &manifest[..], args].concat());
+            let mut files: Vec<_> = source_files(&walk_options(&args).unwrap())
+                .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            files.sort();
+            files
+        };
+
+        assert_eq!(vec!["Baz.java", "Foo.java"], files(&[]));
+        assert_eq!(vec!["Foo.java"], files(&["--package", "a.b"]));
+
+        // Missing files are read failures
+        let missing = dir.path().join("a/b/Missing.java");
+        fs::write(&manifest, missing.to_str().unwrap()).unwrap();
+        assert_eq!(vec!["Missing.java"], files(&[]));
+        let output = dir.path().join("out.dot");
+        let args = Args::parse_from([
+            "spring-visualizer",
+            "--files-from",
+            manifest.to_str().unwrap(),
+            "--output",
+            output.to_str().unwrap(),
+        ]);
+        let walk_options = walk_options(&args).unwrap();
+        assert_eq!(
+            Exit::ParseFailures,
+            run(&args, &args.graph, &walk_options, &ParseOptions::default())
+        );
+    }
+
+    #[test]
+    fn line_column_of_remaining_input() {
+        let source = "package a;\n\npublic clas Foo {}\n";
+        let remaining = &source[source.find("clas").unwrap()..];
+        assert_eq!((3, 8), line_column(source, remaining));
+        assert_eq!((1, 1), line_column(source, source));
+        assert_eq!((1, 1), line_column(source, "unrelated"));
+    }
+
+    #[test]
+    fn subcommands() {
+        let command =
+            |args: &[&str]| Args::parse_from([&["spring-visualizer"], args].concat()).command();
+
+        let path = |args: &[&str]| {
+            let args = Args::parse_from([&["spring-visualizer"], args].concat());
+            args.path().map(String::from)
+        };
+
+        assert!(matches!(command(&["demo"]), Command::Graph { graph, .. } if !graph.deterministic));
+        assert_eq!(Some("demo".to_string()), path(&["demo"]));
+        assert!(matches!(
+            command(&["graph", "demo", "--deterministic"]),
+            Command::Graph { graph, .. } if graph.deterministic
+        ));
+        assert_eq!(Some("demo".to_string()), path(&["graph", "demo"]));
+        let args = Args::parse_from(["spring-visualizer", "check", "demo", "--package", "a.b"]);
+        assert!(matches!(args.command(), Command::Check { .. }));
+        assert_eq!(Some("demo"), args.path());
+        assert_eq!(Some("a.b"), args.package.as_deref());
+        assert_eq!(
+            Some("a.b".to_string()),
+            Args::parse_from(["spring-visualizer", "--package", "a.b", "check"]).package
+        );
+        assert_eq!(Some("demo".to_string()), path(&["demo", "check"]));
+        // Options of the graph belong after the graph command
+        let parse = |args: &[&str]| Args::try_parse_args([&["spring-visualizer"], args].concat());
+        assert!(parse(&["demo", "--no-legend", "graph"]).is_err());
+        assert!(parse(&["--no-legend", "check"]).is_err());
+        assert!(parse(&["demo", "graph", "other"]).is_err());
+        assert!(parse(&["demo", "--no-legend"]).is_ok());
+        assert!(parse(&["graph", "demo", "--no-legend"]).is_ok());
+        assert!(matches!(
+            command(&["dump", "Foo.java"]),
+            Command::Dump { file } if file == Path::new("Foo.java")
+        ));
+        assert!(matches!(
+            command(&["--dump", "Foo.java"]),
+            Command::Dump { file } if file == Path::new("Foo.java")
+        ));
+        assert!(matches!(command(&["list-features"]), Command::ListFeatures));
+        assert!(matches!(
+            command(&["--list-features"]),
+            Command::ListFeatures
+        ));
+    }
 }
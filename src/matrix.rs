@@ -0,0 +1,179 @@
+//! Adjacency matrix of the dependencies between components.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use crate::{class::Class, render::RenderOptions};
+
+/// Writes a CSV matrix where each cell counts the edges from the component
+/// in the row to the component in the column.
+pub fn render_csv_matrix(
+    out: &mut impl Write,
+    classes: &[Class],
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let graph = options.graph(classes);
+    let names: Vec<&str> = graph
+        .nodes()
+        .iter()
+        .filter(|n| n.component_type().is_some())
+        .map(|n| n.id())
+        .collect();
+
+    let mut counts: HashMap<(&str, &str), usize> = HashMap::new();
+    for edge in graph.edges() {
+        *counts.entry((edge.from(), edge.to())).or_default() += 1;
+    }
+
+    write!(out, "\"\"")?;
+    for name in &names {
+        write!(out, ",{}", quote(name))?;
+    }
+    writeln!(out)?;
+    for from in &names {
+        write!(out, "{}", quote(from))?;
+        for to in &names {
+            let count = counts.get(&(*from, *to)).copied().unwrap_or_default();
+            write!(out, ",{}", count)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Quotes a CSV field, doubling the quotes in it.
+fn quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        autowired::Autowired,
+        bean::{Bean, Parameter},
+        class::{Class, ClassBuilder},
+        component_type::ComponentType,
+        render::{RenderOptions, RenderOptionsBuilder},
+    };
+
+    use super::{quote, render_csv_matrix};
+
+    fn component(name: &str, component_type: ComponentType, autowires: &[&str]) -> ClassBuilder {
+        let mut builder = ClassBuilder::default();
+        builder
+            .package("a.b.c".to_string())
+            .name(name.to_string())
+            .component_type(Some(component_type))
+            .autowires(
+                autowires
+                    .iter()
+                    .map(|a| Autowired::new(a.to_string(), a.to_lowercase()))
+                    .collect(),
+            );
+        builder
+    }
+
+    #[test]
+    fn render_csv_matrix_counts_edges() {
+        let classes: Vec<Class> = vec![
+            component("Controller", ComponentType::Controller, &["Service"])
+                .parameters(vec![Parameter::new(
+                    vec![],
+                    "Service".to_string(),
+                    "service".to_string(),
+                )])
+                .build()
+                .unwrap(),
+            component("Service", ComponentType::Component, &["Repo"])
+                .build()
+                .unwrap(),
+            component("Repo", ComponentType::Component, &[])
+                .build()
+                .unwrap(),
+        ];
+        let mut out = Vec::new();
+        render_csv_matrix(&mut out, &classes, &RenderOptions::default()).unwrap();
+        assert_eq!(
+            "\"\",\"Controller\",\"Service\",\"Repo\"\n\
+             \"Controller\",0,2,0\n\
+             \"Service\",0,0,1\n\
+             \"Repo\",0,0,0\n",
+            String::from_utf8(out).unwrap()
+        );
+
+        let options = RenderOptionsBuilder::default()
+            .merge_parallel_edges(true)
+            .build()
+            .unwrap();
+        let mut out = Vec::new();
+        render_csv_matrix(&mut out, &classes, &options).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("\"Controller\",0,1,0\n"));
+    }
+
+    #[test]
+    fn render_csv_matrix_only_types() {
+        let classes: Vec<Class> = vec![
+            component("Controller", ComponentType::Controller, &["Service"])
+                .build()
+                .unwrap(),
+            component("Service", ComponentType::Service, &["Repo"])
+                .build()
+                .unwrap(),
+            component("Repo", ComponentType::Repository, &[])
+                .build()
+                .unwrap(),
+        ];
+        let options = RenderOptionsBuilder::default()
+            .only_types(vec![ComponentType::Service, ComponentType::Repository])
+            .build()
+            .unwrap();
+        let mut out = Vec::new();
+        render_csv_matrix(&mut out, &classes, &options).unwrap();
+        assert_eq!(
+            "\"\",\"Service\",\"Repo\"\n\
+             \"Service\",0,1\n\
+             \"Repo\",0,0\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn render_csv_matrix_counts_import_and_bean_edges() {
+        let classes: Vec<Class> = vec![
+            component("AppConfig", ComponentType::Configuration, &[])
+                .imports(vec!["DbConfig".to_string()])
+                .bean_defs(vec![Bean::new(
+                    "Repo".to_string(),
+                    "repo".to_string(),
+                    vec![],
+                )])
+                .build()
+                .unwrap(),
+            component("DbConfig", ComponentType::Configuration, &[])
+                .build()
+                .unwrap(),
+            component("Repo", ComponentType::Repository, &[])
+                .build()
+                .unwrap(),
+        ];
+        let mut out = Vec::new();
+        render_csv_matrix(&mut out, &classes, &RenderOptions::default()).unwrap();
+        assert_eq!(
+            "\"\",\"AppConfig\",\"DbConfig\",\"Repo\"\n\
+             \"AppConfig\",0,1,1\n\
+             \"DbConfig\",0,0,0\n\
+             \"Repo\",0,0,0\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn quote_doubles_quotes() {
+        assert_eq!("\"Foo\"", quote("Foo"));
+        assert_eq!("\"a \"\"b\"\"\"", quote("a \"b\""));
+    }
+}
@@ -0,0 +1,147 @@
+//! Colors of the component types and edge kinds.
+
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{component_type::ComponentType, graph::EdgeKind};
+
+/// Set of fill colors for the component types and line colors for the edges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Palette {
+    /// The colors of [`ComponentType::color_code`].
+    #[default]
+    Default,
+    /// Okabe-Ito colors, which stay distinguishable with color vision deficiencies.
+    Colorblind,
+}
+
+impl Palette {
+    pub fn color(&self, component_type: &ComponentType) -> &'static str {
+        match self {
+            Palette::Default => component_type.color_code(),
+            Palette::Colorblind => match component_type {
+                ComponentType::SpringBootApplication => "#009e73",
+                ComponentType::Configuration => "#56b4e9",
+                ComponentType::Controller => "#cc79a7",
+                // Okabe-Ito has no color left, so rest controllers share the one of controllers
+                // and are told apart by their shape
+                ComponentType::RestController => "#cc79a7",
+                ComponentType::Service => "#0072b2",
+                ComponentType::Repository => "#e69f00",
+                ComponentType::Component => "#f0e442",
+                ComponentType::FeignClient => "#d55e00",
+                ComponentType::Mapper => "#999999",
+                ComponentType::TestConfiguration => "#dddddd",
+            },
+        }
+    }
+
+    pub fn edge_color(&self, kind: EdgeKind) -> &'static str {
+        match self {
+            Palette::Default => match kind {
+                EdgeKind::Import | EdgeKind::ComponentScan => "#404040",
+                EdgeKind::Contains | EdgeKind::Implements => "#7f7f7f",
+                EdgeKind::Autowired => "#1f77b4",
+                EdgeKind::ConstructorInjection => "#2ca02c",
+                EdgeKind::BeanInjection => "#9467bd",
+                EdgeKind::Bean => "#6b1d1d",
+                EdgeKind::Value => "#8c564b",
+                EdgeKind::Entity => "#17becf",
+                EdgeKind::FeignClient => "#5c8a3a",
+            },
+            Palette::Colorblind => match kind {
+                EdgeKind::Import | EdgeKind::ComponentScan | EdgeKind::Entity => "#000000",
+                EdgeKind::Contains | EdgeKind::Implements => "#999999",
+                EdgeKind::Autowired => "#0072b2",
+                EdgeKind::ConstructorInjection => "#009e73",
+                EdgeKind::BeanInjection => "#cc79a7",
+                EdgeKind::Bean => "#e69f00",
+                EdgeKind::Value => "#56b4e9",
+                EdgeKind::FeignClient => "#d55e00",
+            },
+        }
+    }
+}
+
+/// Colors replacing those of the palette, read from a palette file.
+///
+/// Each line of the file sets the color of a component type or an edge kind, as in
+/// `node.Service = #1f77b4` or `edge.Autowired = blue`. Names are case-insensitive,
+/// and empty lines and lines starting with `#` are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColorOverrides {
+    nodes: HashMap<ComponentType, String>,
+    edges: HashMap<EdgeKind, String>,
+}
+
+impl ColorOverrides {
+    /// The fill color of a component type, unless it is left to the palette.
+    pub fn node(&self, component_type: &ComponentType) -> Option<&str> {
+        self.nodes.get(component_type).map(String::as_str)
+    }
+
+    /// The line color of an edge kind, unless it is left to the palette.
+    pub fn edge(&self, kind: EdgeKind) -> Option<&str> {
+        self.edges.get(&kind).map(String::as_str)
+    }
+}
+
+impl FromStr for ColorOverrides {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut overrides = ColorOverrides::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = |reason: &str| format!("line {}: {}: {}", i + 1, reason, line);
+            let (key, color) = line.split_once('=').ok_or_else(|| {
+                invalid("expected `node.<type> = <color>` or `edge.<kind> = <color>`")
+            })?;
+            let (key, color) = (key.trim(), color.trim().to_string());
+            if color.is_empty() {
+                return Err(invalid("missing color"));
+            }
+            if let Some(name) = key.strip_prefix("node.") {
+                let component_type = name
+                    .parse()
+                    .map_err(|_| invalid("unknown component type"))?;
+                overrides.nodes.insert(component_type, color);
+            } else if let Some(name) = key.strip_prefix("edge.") {
+                let kind = name.parse().map_err(|_| invalid("unknown edge kind"))?;
+                overrides.edges.insert(kind, color);
+            } else {
+                return Err(invalid("expected a key starting with `node.` or `edge.`"));
+            }
+        }
+        Ok(overrides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{component_type::ComponentType, graph::EdgeKind};
+
+    use super::ColorOverrides;
+
+    #[test]
+    fn parse_color_overrides() {
+        let overrides: ColorOverrides = "# Our colors\n\nnode.service = #123456\nedge.Bean=red\n"
+            .parse()
+            .unwrap();
+        assert_eq!(Some("#123456"), overrides.node(&ComponentType::Service));
+        assert_eq!(None, overrides.node(&ComponentType::Controller));
+        assert_eq!(Some("red"), overrides.edge(EdgeKind::Bean));
+        assert_eq!(None, overrides.edge(EdgeKind::Autowired));
+
+        for invalid in [
+            "node.Service",
+            "node.Nothing = red",
+            "edge.Bean =",
+            "Bean = red",
+        ] {
+            assert!(invalid.parse::<ColorOverrides>().is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,1497 @@
+//! Rendering of parsed classes as a Graphviz graph.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    io::{self, Write},
+    str::FromStr,
+};
+
+use clap::Parser;
+use derive_builder::Builder;
+use strum::IntoEnumIterator;
+
+use crate::{
+    class::Class,
+    component_type::ComponentType,
+    feature::Features,
+    graph::{package_dependencies, Edge, EdgeKind, Graph, Node, NodeIds},
+    palette::{ColorOverrides, Palette},
+};
+
+/// Line and text color of nodes for types that were not found among the sources.
+const EXTERNAL_COLOR: &str = "#7f7f7f";
+/// Color of the nodes and edges added since the baseline of a comparison.
+const ADDED_COLOR: &str = "green";
+/// Color of the nodes and edges removed since the baseline of a comparison.
+const REMOVED_COLOR: &str = "red";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Parser)]
+pub enum Direction {
+    /// Left to right.
+    #[default]
+    LeftToRight,
+    /// Top to bottom.
+    TopToBottom,
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::LeftToRight => write!(f, "LR"),
+            Direction::TopToBottom => write!(f, "TB"),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "LR" => Ok(Direction::LeftToRight),
+            "RB" => Ok(Direction::TopToBottom),
+            _ => Err(format!("unknown direction {}", s)),
+        }
+    }
+}
+
+/// Output format of the graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Graphviz DOT source.
+    #[default]
+    Dot,
+    /// HTML page rendering the DOT source in the browser.
+    Html,
+    /// CSV adjacency matrix counting the edges between components.
+    CsvMatrix,
+    /// JSON of the nodes and edges, which `--compare` reads.
+    Json,
+}
+
+impl Format {
+    /// File extension for output in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Dot => "dot",
+            Format::Html => "html",
+            Format::CsvMatrix => "csv",
+            Format::Json => "json",
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::CsvMatrix => write!(f, "csv-matrix"),
+            _ => write!(f, "{}", self.extension()),
+        }
+    }
+}
+
+/// How classes are named in node labels.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LabelStyle {
+    /// The class name, such as `Foo`.
+    #[default]
+    Simple,
+    /// The package and class name, such as `com.example.Foo`.
+    Qualified,
+}
+
+impl Display for LabelStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LabelStyle::Simple => write!(f, "simple"),
+            LabelStyle::Qualified => write!(f, "qualified"),
+        }
+    }
+}
+
+impl FromStr for LabelStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "simple" => Ok(LabelStyle::Simple),
+            "qualified" => Ok(LabelStyle::Qualified),
+            _ => Err(format!("unknown label style {}", s)),
+        }
+    }
+}
+
+/// Background and text colors of the graph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+    /// Graphviz defaults, dark text on white.
+    #[default]
+    Light,
+    /// Light text on a dark background, for dark-mode documentation.
+    Dark,
+}
+
+/// Background of the dark theme.
+pub(crate) const DARK_BACKGROUND: &str = "#1e1e1e";
+/// Text and line color of the dark theme.
+pub(crate) const DARK_FOREGROUND: &str = "#e0e0e0";
+/// Fill of filled nodes without a color of their own in the dark theme.
+const DARK_FILL: &str = "#3c3c3c";
+
+impl Theme {
+    /// The color to draw an edge of `color` in. The darkest edge colors would
+    /// disappear on a dark background, so they are drawn in the text color instead.
+    pub fn edge_color<'a>(&self, color: &'a str) -> &'a str {
+        match self {
+            Theme::Light => color,
+            Theme::Dark if luminance(color).is_some_and(|l| l < 0.3) => DARK_FOREGROUND,
+            Theme::Dark => color,
+        }
+    }
+
+    /// The font color attribute of a node filled with `fill`. The light text of the dark
+    /// theme cannot be read on light fills, so they keep dark text.
+    fn fill_font_color(&self, fill: &str) -> &'static str {
+        match self {
+            Theme::Dark if !matches!(luminance(fill), Some(l) if l < 0.4) => {
+                ",fontcolor=\"#000000\""
+            }
+            _ => "",
+        }
+    }
+}
+
+/// Approximate luminance between 0 and 1 of a `#rrggbb` color.
+fn luminance(color: &str) -> Option<f64> {
+    let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let channel = |i: usize| {
+        u8::from_str_radix(&hex[i..i + 2], 16)
+            .ok()
+            .map(|c| f64::from(c) / 255.0)
+    };
+    Some(0.2126 * channel(0)? + 0.7152 * channel(2)? + 0.0722 * channel(4)?)
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Theme::Light => write!(f, "light"),
+            Theme::Dark => write!(f, "dark"),
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "light" => Ok(Theme::Light),
+            "dark" => Ok(Theme::Dark),
+            _ => Err(format!("unknown theme {}", s)),
+        }
+    }
+}
+
+/// Image format that Graphviz renders the DOT source into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+impl ImageFormat {
+    /// File extension for images in this format, also the name Graphviz knows it by.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Svg => "svg",
+            ImageFormat::Png => "png",
+        }
+    }
+}
+
+impl Display for ImageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "svg" => Ok(ImageFormat::Svg),
+            "png" => Ok(ImageFormat::Png),
+            _ => Err(format!("unknown image format {}", s)),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Format::Dot),
+            "html" => Ok(Format::Html),
+            "csv-matrix" => Ok(Format::CsvMatrix),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Builder)]
+pub struct RenderOptions {
+    #[builder(default)]
+    features: Features,
+    #[builder(default)]
+    direction: Direction,
+    #[builder(default = "true")]
+    legend: bool,
+    /// Include the injected field or parameter name in edge labels.
+    #[builder(default)]
+    show_names: bool,
+    /// Title shown at the top of the graph.
+    #[builder(default)]
+    title: Option<String>,
+    /// Maximum number of characters of edge labels, unlimited if unset.
+    #[builder(default)]
+    edge_label_max_len: Option<usize>,
+    /// Draw one node per package instead of one per class.
+    #[builder(default)]
+    aggregate_by_package: bool,
+    /// Component types to draw, all of them if empty.
+    #[builder(default)]
+    only_types: Vec<ComponentType>,
+    /// Add layout hints, so that the same classes always give the same graph. The classes are
+    /// drawn in the order given, sorted with [`crate::class::sort_classes`] when parsed.
+    #[builder(default)]
+    deterministic: bool,
+    /// Colors of the component types and edges.
+    #[builder(default)]
+    palette: Palette,
+    /// Colors replacing those of the palette.
+    #[builder(default)]
+    color_overrides: ColorOverrides,
+    /// Place applications first and repositories without dependencies last.
+    #[builder(default)]
+    rank_entrypoints: bool,
+    /// Whether node labels show simple or qualified class names.
+    #[builder(default)]
+    label_style: LabelStyle,
+    /// The searched path, recorded in the header comments.
+    #[builder(default)]
+    source_path: Option<String>,
+    /// Draw imports of classes that were not parsed as edges to their package.
+    #[builder(default)]
+    group_external_imports: bool,
+    /// Background and text colors.
+    #[builder(default)]
+    theme: Theme,
+    /// Draw a single edge between two nodes joined more than once.
+    #[builder(default)]
+    merge_parallel_edges: bool,
+}
+
+impl RenderOptions {
+    pub fn features(&self) -> &Features {
+        &self.features
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+
+    /// Fill color of a component type.
+    fn node_color(&self, component_type: &ComponentType) -> &str {
+        self.color_overrides
+            .node(component_type)
+            .unwrap_or_else(|| self.palette.color(component_type))
+    }
+
+    /// Line color of an edge kind.
+    fn kind_color(&self, kind: EdgeKind) -> &str {
+        self.color_overrides
+            .edge(kind)
+            .unwrap_or_else(|| self.palette.edge_color(kind))
+    }
+
+    /// Whether classes of the given component type are drawn. Rest controllers are
+    /// drawn along with the controllers.
+    pub(crate) fn shows(&self, component_type: &ComponentType) -> bool {
+        self.only_types.is_empty() || self.only_types.iter().any(|t| component_type.is_a(t))
+    }
+
+    /// The node ids of the classes of types left out by the options.
+    fn hidden(&self, classes: &[Class], ids: &NodeIds) -> HashSet<String> {
+        classes
+            .iter()
+            .filter(|c| c.component_type().is_some_and(|t| !self.shows(t)))
+            .map(|c| ids.id(c))
+            .collect()
+    }
+
+    /// The graph of the classes with the enabled features, leaving out the types that are not
+    /// shown, with parallel edges merged if asked to.
+    pub fn graph(&self, classes: &[Class]) -> Graph {
+        let ids = NodeIds::new(classes);
+        let hidden = self.hidden(classes, &ids);
+        let parsed: HashSet<String> = classes.iter().map(|c| ids.id(c)).collect();
+        let drawn: HashMap<String, &Class> = classes
+            .iter()
+            .map(|c| (ids.drawn_id(c, &self.features), c))
+            .collect();
+        let graph = Graph::with_features(classes, &self.features)
+            .without_nodes(&hidden)
+            .with_node_colors(|t| self.node_color(t))
+            .map_edges(|edge| match edge.name() {
+                Some(name) if self.show_names => {
+                    let label = format!("{} {}", edge.label(), name);
+                    edge.with_label(label)
+                }
+                _ if edge.kind() == EdgeKind::Import
+                    && self.group_external_imports
+                    && !parsed.contains(edge.to()) =>
+                {
+                    let Some(class) = drawn.get(edge.from()) else {
+                        return edge;
+                    };
+                    let import = edge.to();
+                    let simple_name = import.rsplit('.').next().unwrap_or(import);
+                    let label = format!("{} {}", edge.label(), simple_name);
+                    let package = external_package(class, import);
+                    Edge::new(edge.from().to_string(), package, edge.kind()).with_label(label)
+                }
+                _ => edge,
+            });
+        if self.merge_parallel_edges {
+            graph.merge_parallel_edges()
+        } else {
+            graph
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptionsBuilder::default()
+            .build()
+            .expect("all fields have defaults")
+    }
+}
+
+/// Builds the label attribute of an edge. Labels longer than the maximum length are
+/// cut to that length, ending in `...`, with the full label kept as a tooltip.
+fn label_attribute(label: &str, options: &RenderOptions) -> String {
+    match options.edge_label_max_len {
+        Some(max_len) if label.chars().count() > max_len => {
+            let ellipsis = if max_len > 3 { "..." } else { "" };
+            let kept = max_len - ellipsis.len();
+            let truncated: String = label.chars().take(kept).collect();
+            format!(
+                "label=\"{}{}\",tooltip=\"{}\"",
+                escape_label(&truncated),
+                ellipsis,
+                escape_label(label)
+            )
+        }
+        _ => format!("label=\"{}\"", escape_label(label)),
+    }
+}
+
+/// The package of an imported class that was not parsed, from its qualified name,
+/// the Java import of its simple name, or else the package of the importing class.
+fn external_package(class: &Class, import: &str) -> String {
+    let qualified = if import.contains('.') {
+        import.to_string()
+    } else {
+        class
+            .java_imports()
+            .iter()
+            .find(|i| i.rsplit('.').next() == Some(import))
+            .cloned()
+            .unwrap_or_else(|| format!("{}.{}", class.package(), import))
+    };
+    match qualified.rsplit_once('.') {
+        Some((package, _)) => package.to_string(),
+        None => qualified,
+    }
+}
+
+/// Edge color attributes, marking an injection that Spring cannot resolve without a
+/// qualifier in red.
+fn edge_color(kind: EdgeKind, ambiguous: bool, options: &RenderOptions) -> String {
+    if ambiguous {
+        ",color=red,fontcolor=red".to_string()
+    } else {
+        let color = options.theme.edge_color(options.kind_color(kind));
+        format!(",color=\"{}\"", color)
+    }
+}
+
+/// Writes an edge between node ids, with the given style attributes before its color.
+fn write_edge(
+    out: &mut impl Write,
+    edge: &Edge,
+    style: &str,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "    {} -> {} [{}{}{}];",
+        quote_id(edge.from()),
+        quote_id(edge.to()),
+        label_attribute(edge.label(), options),
+        style,
+        edge_color(edge.kind(), edge.ambiguous(), options)
+    )
+}
+
+fn write_legend(out: &mut impl Write, options: &RenderOptions) -> io::Result<()> {
+    // Only the types that are drawn
+    let types: Vec<_> = ComponentType::iter().filter(|t| options.shows(t)).collect();
+    writeln!(out, "    # Legend")?;
+    for component_type in &types {
+        writeln!(
+            out,
+            "    \"@{:?}\" [fillcolor=\"{}\",style=filled,shape={}{}];",
+            component_type,
+            options.node_color(component_type),
+            component_type.shape(),
+            options
+                .theme
+                .fill_font_color(options.node_color(component_type))
+        )?;
+    }
+
+    writeln!(out, "    \"Edges\" [shape=none,label=<<table border=\"0\">")?;
+    for kind in EdgeKind::iter() {
+        writeln!(
+            out,
+            "        <tr><td><font color=\"{}\">{}</font></td></tr>",
+            options.theme.edge_color(options.kind_color(kind)),
+            kind.label()
+        )?;
+    }
+    writeln!(out, "    </table>>];")?;
+
+    writeln!(out)?;
+
+    writeln!(out, "    # Align legend")?;
+    for (cur, next) in types.iter().zip(types.iter().skip(1)) {
+        writeln!(out, r#"    "@{:?}" -> "@{:?}" [style=invis];"#, cur, next)?;
+    }
+    if let Some(last) = types.last() {
+        writeln!(out, r#"    "@{:?}" -> "Edges" [style=invis];"#, last)?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Quotes a node id unless it is a plain DOT identifier.
+fn quote_id(id: &str) -> String {
+    if id.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        id.to_string()
+    } else {
+        format!("\"{}\"", escape_label(id))
+    }
+}
+
+/// Escapes the double quotes of a string put between double quotes. Backslashes are
+/// kept, as DOT uses them for line breaks such as `\n`.
+fn escape_label(label: &str) -> String {
+    label.replace('"', "\\\"")
+}
+
+/// Writes comments recording how the graph was produced, which Graphviz ignores.
+fn write_header(out: &mut impl Write, options: &RenderOptions) -> io::Result<()> {
+    writeln!(out, "// features: {}", options.features)?;
+    if let Some(path) = &options.source_path {
+        writeln!(out, "// path: {}", path)?;
+    }
+    writeln!(out, "// version: {}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Writes the graph attributes shared by class and package graphs.
+fn write_graph_attributes(out: &mut impl Write, options: &RenderOptions) -> io::Result<()> {
+    writeln!(out, "    rankdir={};", options.direction)?;
+    if options.deterministic {
+        writeln!(out, "    ordering=out;")?;
+    }
+    if let Some(title) = &options.title {
+        writeln!(out, "    label=\"{}\";", escape_label(title))?;
+        writeln!(out, "    labelloc=t;")?;
+    }
+    if options.theme == Theme::Dark {
+        writeln!(out, "    bgcolor=\"{}\";", DARK_BACKGROUND)?;
+        writeln!(out, "    fontcolor=\"{}\";", DARK_FOREGROUND)?;
+        writeln!(
+            out,
+            "    node [color=\"{}\",fontcolor=\"{}\",fillcolor=\"{}\"];",
+            DARK_FOREGROUND, DARK_FOREGROUND, DARK_FILL
+        )?;
+        writeln!(
+            out,
+            "    edge [color=\"{}\",fontcolor=\"{}\"];",
+            DARK_FOREGROUND, DARK_FOREGROUND
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the dependencies between packages as a DOT graph, labelling each edge
+/// with the number of dependencies it stands for.
+fn render_packages(
+    out: &mut impl Write,
+    classes: &[Class],
+    options: &RenderOptions,
+) -> io::Result<()> {
+    write_header(out, options)?;
+    writeln!(out, "digraph Packages {{")?;
+    write_graph_attributes(out, options)?;
+    let packages: BTreeSet<&str> = classes
+        .iter()
+        .filter(|c| c.component_type().is_some())
+        .map(|c| c.package())
+        .collect();
+    for package in packages {
+        writeln!(out, "    \"{}\" [shape=folder];", package)?;
+    }
+    for ((from, to), count) in package_dependencies(classes, &options.features) {
+        let unit = if count == 1 { "dep" } else { "deps" };
+        let label = label_attribute(&format!("{} {}", count, unit), options);
+        writeln!(out, "    \"{}\" -> \"{}\" [{}];", from, to, label)?;
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Writes a graph compared to an earlier one as a DOT graph, with the nodes and edges
+/// added since then in green and the removed ones dashed in red.
+pub fn render_comparison(
+    out: &mut impl Write,
+    current: &Graph,
+    baseline: &Graph,
+    options: &RenderOptions,
+) -> io::Result<()> {
+    let diff = current.compare(baseline);
+    write_header(out, options)?;
+    writeln!(out, "digraph Comparison {{")?;
+    write_graph_attributes(out, options)?;
+    // Removed nodes are not filled, so that their dashed outline shows
+    let write_node = |out: &mut dyn Write, node: &Node, filled: bool, style: &str| {
+        let fill = node
+            .component_type()
+            .filter(|_| filled)
+            .map(|t| options.node_color(t))
+            .map(|c| {
+                let font = options.theme.fill_font_color(c);
+                format!(",fillcolor=\"{}\",style=filled{}", c, font)
+            })
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "    {} [label=\"{}\"{}{}];",
+            quote_id(node.id()),
+            escape_label(node.label()),
+            fill,
+            style
+        )
+    };
+    let write_diff_edge = |out: &mut dyn Write, edge: &Edge, style: &str| {
+        writeln!(
+            out,
+            "    {} -> {} [{}{}];",
+            quote_id(edge.from()),
+            quote_id(edge.to()),
+            label_attribute(edge.label(), options),
+            style
+        )
+    };
+    let added = format!(
+        ",color={},fontcolor={},penwidth=2",
+        ADDED_COLOR, ADDED_COLOR
+    );
+    let removed = format!(
+        ",color={},fontcolor={},style=dashed",
+        REMOVED_COLOR, REMOVED_COLOR
+    );
+    let added_nodes: HashSet<&str> = diff.added_nodes.iter().map(Node::id).collect();
+    let added_edges: HashSet<_> = diff.added_edges.iter().map(Edge::key).collect();
+    for node in current.nodes() {
+        let style = if added_nodes.contains(node.id()) {
+            added.as_str()
+        } else {
+            ""
+        };
+        write_node(out, node, true, style)?;
+    }
+    for node in &diff.removed_nodes {
+        write_node(out, node, false, &removed)?;
+    }
+    for edge in current.edges() {
+        let style = if added_edges.contains(&edge.key()) {
+            added.as_str()
+        } else {
+            ""
+        };
+        write_diff_edge(out, edge, style)?;
+    }
+    for edge in &diff.removed_edges {
+        write_diff_edge(out, edge, &removed)?;
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Writes the classes as a DOT graph.
+pub fn render(out: &mut impl Write, classes: &[Class], options: &RenderOptions) -> io::Result<()> {
+    if options.aggregate_by_package {
+        return render_packages(out, classes, options);
+    }
+    let features = &options.features;
+
+    write_header(out, options)?;
+    writeln!(out, "digraph Components {{")?;
+    write_graph_attributes(out, options)?;
+
+    if options.legend {
+        write_legend(out, options)?;
+    }
+
+    let ids = NodeIds::new(classes);
+    let graph = options.graph(classes);
+    // The class each node is drawn for, preferring components when implementations are
+    // combined with their interfaces
+    let mut drawn: HashMap<String, &Class> = HashMap::new();
+    for class in classes {
+        drawn
+            .entry(ids.drawn_id(class, features))
+            .and_modify(|c| {
+                if c.component_type().is_none() {
+                    *c = class
+                }
+            })
+            .or_insert(class);
+    }
+    // Nodes of parsed classes and beans, and the nodes edges point to, which are
+    // drawn as external if they are not among the former
+    let mut defined: HashSet<&str> = graph.nodes().iter().map(Node::id).collect();
+    let mut referenced = BTreeSet::new();
+    // Packages of imported classes that were not parsed, when grouping them
+    let mut external_packages = BTreeSet::new();
+    // Classes without a component type are not drawn, nor are the edges they draw
+    let mut skipped = HashSet::new();
+    // Beans drawn for the configurations that are drawn
+    let mut beans = HashSet::new();
+    // Nodes placed in the first and last rank
+    let mut sources = Vec::new();
+    let mut sinks = Vec::new();
+
+    for node in graph.nodes() {
+        let id = node.id();
+        let name = quote_id(id);
+        let class = drawn[id];
+        let Some(component_type) = node.component_type() else {
+            tracing::trace!("Skipping class without component type: {}", name);
+            skipped.insert(id);
+            continue;
+        };
+        let color = node.color().unwrap_or(component_type.color_code());
+        let lifecycle = match (class.post_construct(), class.pre_destroy()) {
+            (true, true) => " (init/destroy)",
+            (true, false) => " (init)",
+            (false, true) => " (destroy)",
+            (false, false) => "",
+        };
+        // Ids are only qualified to tell classes apart, the label style decides what is shown
+        let shown_name = match options.label_style {
+            LabelStyle::Qualified if id == ids.id(class) => class.qualified_name(),
+            _ => node.label().to_string(),
+        };
+        let label = if lifecycle.is_empty() && shown_name == id {
+            String::new()
+        } else {
+            format!(",label=\"{}{}\"", shown_name, lifecycle)
+        };
+        // Grouping nodes by type keeps them in the same place between runs
+        let group = if options.deterministic {
+            format!(",group={}", component_type.plural())
+        } else {
+            String::new()
+        };
+        let order = class.order().map(|o| format!("order: {}", o));
+        let excludes = Some(class.excluded_autoconfigs())
+            .filter(|e| !e.is_empty())
+            .map(|e| format!("excludes: {}", e.join(", ")));
+        let path = class.base_path().map(|p| format!("path: {}", p));
+        let details: Vec<_> = order.into_iter().chain(excludes).chain(path).collect();
+        let tooltip = if details.is_empty() {
+            String::new()
+        } else {
+            format!(",tooltip=\"{}\"", details.join("\\n"))
+        };
+        if options.rank_entrypoints {
+            match component_type {
+                ComponentType::SpringBootApplication => sources.push(name.clone()),
+                // Implemented interfaces are drawn as edges into the class
+                ComponentType::Repository
+                    if class
+                        .relations(features)
+                        .all(|r| r.kind == EdgeKind::Implements) =>
+                {
+                    sinks.push(name.clone())
+                }
+                _ => {}
+            }
+        }
+        writeln!(
+            out,
+            "    {} [fillcolor=\"{}\"style=filled,shape={}{}{}{}{}];",
+            name,
+            color,
+            component_type.shape(),
+            options.theme.fill_font_color(color),
+            label,
+            group,
+            tooltip
+        )?;
+    }
+
+    for edge in graph.edges() {
+        let kind = edge.kind();
+        let skip = match kind {
+            // Implemented interfaces are drawn as edges into the class
+            EdgeKind::Implements => skipped.contains(edge.to()),
+            // Injections into beans are drawn along with the beans
+            EdgeKind::BeanInjection => !beans.contains(edge.from()),
+            _ => skipped.contains(edge.from()),
+        };
+        if skip {
+            continue;
+        }
+        let mut style = String::new();
+        match kind {
+            EdgeKind::Import if defined.contains(edge.to()) => {}
+            EdgeKind::Import if options.group_external_imports => {
+                external_packages.insert(edge.to());
+            }
+            EdgeKind::Import | EdgeKind::Entity => {
+                referenced.insert(edge.to());
+            }
+            EdgeKind::Implements => {
+                referenced.insert(edge.from());
+            }
+            EdgeKind::Autowired | EdgeKind::ConstructorInjection | EdgeKind::BeanInjection => {
+                referenced.insert(edge.to());
+                if edge.optional() {
+                    style.push_str(",style=dashed");
+                }
+                // A collection binds to every bean of the type, so draw a many-end
+                if kind == EdgeKind::Autowired && edge.collection() {
+                    style.push_str(",arrowhead=crow");
+                }
+            }
+            EdgeKind::ComponentScan => {
+                writeln!(out, "    \"{}\" [style=filled];", edge.to())?;
+            }
+            EdgeKind::Value => {
+                writeln!(out, "    \"{}\" [shape=note];", edge.to())?;
+            }
+            EdgeKind::FeignClient => {
+                writeln!(out, "    \"{}\" [shape=box3d];", edge.to())?;
+                style.push_str(",style=dashed");
+            }
+            EdgeKind::Bean => {
+                defined.insert(edge.to());
+                beans.insert(edge.to());
+                let class = drawn[edge.from()];
+                let bean = class
+                    .bean_defs()
+                    .iter()
+                    .find(|b| ids.resolve(class, b.class()) == edge.to());
+                let order = bean.and_then(|b| b.order()).map(|o| o.to_string());
+                let details = [
+                    ("impl", bean.and_then(|b| b.implementation())),
+                    ("init", bean.and_then(|b| b.init_method())),
+                    ("destroy", bean.and_then(|b| b.destroy_method())),
+                    ("order", order.as_deref()),
+                ]
+                .into_iter()
+                .filter_map(|(kind, detail)| detail.map(|d| format!("{}: {}", kind, d)))
+                .collect::<Vec<_>>();
+                let tooltip = if details.is_empty() {
+                    String::new()
+                } else {
+                    format!(",tooltip=\"{}\"", details.join(", "))
+                };
+                writeln!(
+                    out,
+                    "    {} [fillcolor=\"#6b1d1d\",style=filled{}];",
+                    quote_id(edge.to()),
+                    tooltip
+                )?;
+            }
+            EdgeKind::Contains => {}
+        }
+        write_edge(out, edge, &style, options)?;
+    }
+
+    for (rank, nodes) in [("source", &sources), ("sink", &sinks)] {
+        if !nodes.is_empty() {
+            writeln!(out, "    {{ rank={}; {}; }}", rank, nodes.join("; "))?;
+        }
+    }
+
+    // Types that were never parsed, such as library classes
+    for external in referenced.iter().filter(|r| !defined.contains(*r)) {
+        writeln!(
+            out,
+            "    {} [shape=box,style=dashed,color=\"{}\",fontcolor=\"{}\"];",
+            quote_id(external),
+            EXTERNAL_COLOR,
+            EXTERNAL_COLOR
+        )?;
+    }
+    for package in &external_packages {
+        writeln!(
+            out,
+            "    \"{}\" [shape=folder,style=dashed,color=\"{}\",fontcolor=\"{}\"];",
+            package, EXTERNAL_COLOR, EXTERNAL_COLOR
+        )?;
+    }
+
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::autowired::Autowired;
+    use crate::bean::{Bean, Parameter};
+    use crate::class::{sort_classes, Class, ClassBuilder};
+    use crate::component_type::ComponentType;
+    use crate::feign_client::FeignClient;
+
+    use crate::graph::Graph;
+    use crate::palette::Palette;
+
+    use super::{
+        render, render_comparison, LabelStyle, RenderOptions, RenderOptionsBuilder, Theme,
+    };
+
+    fn component(name: &str, component_type: ComponentType, autowires: &[&str]) -> ClassBuilder {
+        let mut builder = ClassBuilder::default();
+        builder
+            .package("a.b.c".to_string())
+            .name(name.to_string())
+            .component_type(Some(component_type))
+            .autowires(
+                autowires
+                    .iter()
+                    .map(|a| Autowired::new(a.to_string(), a.to_lowercase()))
+                    .collect(),
+            );
+        builder
+    }
+
+    fn service() -> Class {
+        component("FooService", ComponentType::Service, &[])
+            .build()
+            .unwrap()
+    }
+
+    fn render_to_string(classes: &[Class], options: &RenderOptions) -> String {
+        let mut out = Vec::new();
+        render(&mut out, classes, options).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn render_includes_legend_by_default() {
+        let output = render_to_string(&[service()], &RenderOptions::default());
+        assert!(output.contains("# Legend"));
+        assert!(output.contains("[style=invis]"));
+        assert!(
+            output.contains("\"@Repository\" [fillcolor=\"#e06907\",style=filled,shape=cylinder];")
+        );
+        assert!(output.contains("FooService [fillcolor=\"#a81347\"style=filled,shape=box];"));
+    }
+
+    #[test]
+    fn render_dark_theme() {
+        let config = component("FooConfig", ComponentType::Configuration, &[])
+            .imports(vec!["FooService".to_string()])
+            .build()
+            .unwrap();
+        let classes = [config, service()];
+        let light = render_to_string(&classes, &RenderOptions::default());
+        assert!(!light.contains("bgcolor"));
+
+        let options = RenderOptionsBuilder::default()
+            .theme(Theme::Dark)
+            .build()
+            .unwrap();
+        let dark = render_to_string(&classes, &options);
+        assert!(dark.contains("    bgcolor=\"#1e1e1e\";\n    fontcolor=\"#e0e0e0\";\n"));
+        assert!(dark
+            .contains("    node [color=\"#e0e0e0\",fontcolor=\"#e0e0e0\",fillcolor=\"#3c3c3c\"];"));
+        assert!(dark.contains("    edge [color=\"#e0e0e0\",fontcolor=\"#e0e0e0\"];"));
+        // Component fills are kept, with dark text on the light ones, while dark edges are lightened
+        assert!(dark.contains("FooService [fillcolor=\"#a81347\"style=filled,shape=box];"));
+        assert!(dark.contains(
+            "FooConfig [fillcolor=\"#28a9e0\"style=filled,shape=component,fontcolor=\"#000000\"];"
+        ));
+        assert!(dark.contains(
+            "\"@Component\" [fillcolor=\"#ffc400\",style=filled,shape=ellipse,fontcolor=\"#000000\"];"
+        ));
+        assert!(dark.contains("FooConfig -> FooService [label=\"@Import\",color=\"#e0e0e0\"];"));
+        assert!(light.contains("FooConfig -> FooService [label=\"@Import\",color=\"#404040\"];"));
+    }
+
+    #[test]
+    fn render_without_legend() {
+        let options = RenderOptionsBuilder::default()
+            .legend(false)
+            .build()
+            .unwrap();
+        let output = render_to_string(&[service()], &options);
+        assert!(!output.contains("# Legend"));
+        assert!(!output.contains("[style=invis]"));
+        assert!(output.contains("\ndigraph Components {\n"));
+        assert!(output.ends_with("}\n"));
+        assert!(output.contains("FooService"));
+    }
+
+    #[test]
+    fn render_repeated_injection_once() {
+        let autowired = Autowired::new("FooRepository".to_string(), "fooRepository".to_string());
+        let class = component("FooService", ComponentType::Service, &[])
+            .autowires(vec![autowired.clone(), autowired])
+            .build()
+            .unwrap();
+        let output = render_to_string(&[class], &RenderOptions::default());
+        assert_eq!(1, output.matches("FooService -> FooRepository").count());
+    }
+
+    #[test]
+    fn render_is_independent_of_file_order() {
+        let repository = component("FooRepository", ComponentType::Repository, &[])
+            .package("a.b.repository".to_string())
+            .build()
+            .unwrap();
+        let controller = component("FooController", ComponentType::Controller, &[])
+            .package("a.b.controller".to_string())
+            .autowires(vec![Autowired::new(
+                "FooService".to_string(),
+                "fooService".to_string(),
+            )])
+            .build()
+            .unwrap();
+
+        let mut first = vec![service(), repository.clone(), controller.clone()];
+        let mut second = vec![repository, controller, service()];
+        sort_classes(&mut first);
+        sort_classes(&mut second);
+
+        let options = RenderOptions::default();
+        assert_eq!(
+            render_to_string(&first, &options),
+            render_to_string(&second, &options)
+        );
+    }
+
+    #[test]
+    fn render_deterministic_sorts_and_groups() {
+        let repository = component("FooRepository", ComponentType::Repository, &[])
+            .package("a.b.repository".to_string())
+            .build()
+            .unwrap();
+        let options = RenderOptionsBuilder::default()
+            .deterministic(true)
+            .build()
+            .unwrap();
+
+        let mut first = vec![service(), repository.clone()];
+        let mut second = vec![repository, service()];
+        sort_classes(&mut first);
+        sort_classes(&mut second);
+        let first = render_to_string(&first, &options);
+        assert_eq!(first, render_to_string(&second, &options));
+        assert!(first.contains("    ordering=out;\n"));
+        assert!(first
+            .contains("FooService [fillcolor=\"#a81347\"style=filled,shape=box,group=services];"));
+    }
+
+    #[test]
+    fn render_feign_client_dependency() {
+        let client = component("BillingClient", ComponentType::FeignClient, &[])
+            .feign_client(Some(FeignClient::new("billing".to_string(), None)))
+            .build()
+            .unwrap();
+        let service = component("FooService", ComponentType::Service, &[])
+            .autowires(vec![Autowired::new(
+                "BillingClient".to_string(),
+                "billingClient".to_string(),
+            )])
+            .build()
+            .unwrap();
+        let output = render_to_string(&[client, service], &RenderOptions::default());
+        assert!(output.contains(
+            "FooService -> billing [label=\"@FeignClient\",style=dashed,color=\"#5c8a3a\"];"
+        ));
+    }
+
+    #[test]
+    fn render_with_names() {
+        let controller = component("FooController", ComponentType::Controller, &[])
+            .autowires(vec![Autowired::new(
+                "FooRepository".to_string(),
+                "repo".to_string(),
+            )])
+            .build()
+            .unwrap();
+
+        let output = render_to_string(std::slice::from_ref(&controller), &RenderOptions::default());
+        assert!(output
+            .contains("FooController -> FooRepository [label=\"@Autowired\",color=\"#1f77b4\"];"));
+
+        let options = RenderOptionsBuilder::default()
+            .show_names(true)
+            .build()
+            .unwrap();
+        let output = render_to_string(&[controller], &options);
+        assert!(output.contains(
+            "FooController -> FooRepository [label=\"@Autowired repo\",color=\"#1f77b4\"];"
+        ));
+    }
+
+    #[test]
+    fn render_title() {
+        let output = render_to_string(&[service()], &RenderOptions::default());
+        assert!(!output.contains("label=\"spring"));
+
+        let options = RenderOptionsBuilder::default()
+            .title(Some("spring-visualizer: \"demo\"".to_string()))
+            .build()
+            .unwrap();
+        let output = render_to_string(&[service()], &options);
+        assert!(
+            output.contains("    label=\"spring-visualizer: \\\"demo\\\"\";\n    labelloc=t;\n")
+        );
+    }
+
+    #[test]
+    fn render_same_named_classes_as_distinct_nodes() {
+        let classes = [
+            component("Config", ComponentType::Configuration, &[])
+                .package("a.b".to_string())
+                .build()
+                .unwrap(),
+            component("Config", ComponentType::Configuration, &[])
+                .package("x.y".to_string())
+                .build()
+                .unwrap(),
+            service(),
+        ];
+        let output = render_to_string(&classes, &RenderOptions::default());
+        assert!(output.contains(
+            "\"a.b.Config\" [fillcolor=\"#28a9e0\"style=filled,shape=component,label=\"Config\"];"
+        ));
+        assert!(output.contains(
+            "\"x.y.Config\" [fillcolor=\"#28a9e0\"style=filled,shape=component,label=\"Config\"];"
+        ));
+        assert!(output.contains("    FooService [fillcolor="));
+    }
+
+    #[test]
+    fn render_only_types() {
+        let repository = component("FooRepository", ComponentType::Repository, &[])
+            .build()
+            .unwrap();
+        let service = component("FooService", ComponentType::Service, &[])
+            .autowires(vec![Autowired::new(
+                "FooRepository".to_string(),
+                "repo".to_string(),
+            )])
+            .build()
+            .unwrap();
+        let options = RenderOptionsBuilder::default()
+            .legend(false)
+            .only_types(vec![ComponentType::Service])
+            .build()
+            .unwrap();
+        // The packages scanned by hidden applications are left out with them
+        let application = component("App", ComponentType::SpringBootApplication, &[])
+            .component_scans(vec!["a.b.c".to_string()])
+            .build()
+            .unwrap();
+        let output = render_to_string(&[repository, service, application], &options);
+        assert!(output.contains("FooService [fillcolor="));
+        assert!(!output.contains("FooRepository"));
+        assert!(!output.contains("a.b.c"));
+    }
+
+    #[test]
+    fn render_only_rest_controllers() {
+        let classes = [
+            component("PageController", ComponentType::Controller, &[])
+                .build()
+                .unwrap(),
+            component("ApiController", ComponentType::RestController, &[])
+                .build()
+                .unwrap(),
+        ];
+        let options = RenderOptionsBuilder::default()
+            .only_types(vec!["restcontroller".parse().unwrap()])
+            .build()
+            .unwrap();
+        let output = render_to_string(&classes, &options);
+        assert!(output.contains("ApiController [fillcolor=\"#a58be0\"style=filled,shape=octagon];"));
+        assert!(!output.contains("PageController"));
+        assert!(output.contains("\"@RestController\" [fillcolor="));
+        assert!(!output.contains("\"@Controller\""));
+        assert!(output.contains(r#""@RestController" -> "Edges" [style=invis];"#));
+
+        // Rest controllers are controllers too
+        let options = RenderOptionsBuilder::default()
+            .only_types(vec!["controller".parse().unwrap()])
+            .build()
+            .unwrap();
+        let output = render_to_string(&classes, &options);
+        assert!(output.contains("PageController [fillcolor="));
+        assert!(output.contains("ApiController [fillcolor="));
+    }
+
+    #[test]
+    fn render_grouped_external_imports() {
+        let config = component("AppConfig", ComponentType::Configuration, &[])
+            .imports(vec![
+                "com.lib.ExternalConfig".to_string(),
+                "OtherConfig".to_string(),
+            ])
+            .java_imports(vec!["com.lib.OtherConfig".to_string()])
+            .build()
+            .unwrap();
+        let options = RenderOptionsBuilder::default()
+            .legend(false)
+            .group_external_imports(true)
+            .build()
+            .unwrap();
+        let output = render_to_string(&[config], &options);
+        assert!(output.contains(r#"AppConfig -> "com.lib" [label="@Import ExternalConfig""#));
+        assert!(output.contains(r#"AppConfig -> "com.lib" [label="@Import OtherConfig""#));
+        assert_eq!(1, output.matches(r#""com.lib" [shape=folder"#).count());
+        assert!(!output.contains("com.lib.ExternalConfig"));
+    }
+
+    #[test]
+    fn render_undefined_types_as_external() {
+        let class = component("FooService", ComponentType::Service, &[])
+            .autowires(vec![
+                Autowired::new("Bar".to_string(), "bar".to_string()),
+                Autowired::new("FooService".to_string(), "self".to_string()),
+            ])
+            .build()
+            .unwrap();
+        let output = render_to_string(&[class], &RenderOptions::default());
+        assert!(output
+            .contains("    Bar [shape=box,style=dashed,color=\"#7f7f7f\",fontcolor=\"#7f7f7f\"];"));
+        assert!(!output.contains("    FooService [shape=box,style=dashed"));
+    }
+
+    #[test]
+    fn render_optional_autowired_dashed() {
+        let class = component("FooService", ComponentType::Service, &[])
+            .autowires(vec![
+                Autowired::new("Bar".to_string(), "bar".to_string()).with_required(false)
+            ])
+            .build()
+            .unwrap();
+        let output = render_to_string(&[class], &RenderOptions::default());
+        assert!(output
+            .contains("FooService -> Bar [label=\"@Autowired\",style=dashed,color=\"#1f77b4\"];"));
+    }
+
+    #[test]
+    fn render_collection_autowired_with_crow_arrowhead() {
+        let class = component("FooService", ComponentType::Service, &[])
+            .autowires(vec![
+                Autowired::new("Bar".to_string(), "bars".to_string()).with_collection(true)
+            ])
+            .build()
+            .unwrap();
+        let output = render_to_string(&[class], &RenderOptions::default());
+        assert!(output.contains(
+            "FooService -> Bar [label=\"@Autowired\",arrowhead=crow,color=\"#1f77b4\"];"
+        ));
+    }
+
+    #[test]
+    fn render_colorblind_palette() {
+        let options = RenderOptionsBuilder::default()
+            .palette(Palette::Colorblind)
+            .build()
+            .unwrap();
+        let output = render_to_string(&[service()], &options);
+        assert!(output.contains("\"@Service\" [fillcolor=\"#0072b2\",style=filled,shape=box];"));
+        assert!(output.contains("FooService [fillcolor=\"#0072b2\"style=filled,shape=box];"));
+        assert!(!output.contains(ComponentType::Service.color_code()));
+    }
+
+    #[test]
+    fn render_order_tooltip() {
+        let class = component("FooFilter", ComponentType::Component, &[])
+            .order(Some(5))
+            .build()
+            .unwrap();
+        let output = render_to_string(&[class], &RenderOptions::default());
+        assert!(output.contains(
+            "FooFilter [fillcolor=\"#ffc400\"style=filled,shape=ellipse,tooltip=\"order: 5\"];"
+        ));
+    }
+
+    #[test]
+    fn render_edge_colors() {
+        let classes = [component("FooConfig", ComponentType::Configuration, &[])
+            .bean_defs(vec![Bean::new(
+                "Foo".to_string(),
+                "foo".to_string(),
+                vec![],
+            )])
+            .build()
+            .unwrap()];
+        let output = render_to_string(&classes, &RenderOptions::default());
+        assert!(output.contains("FooConfig -> Foo [label=\"@Bean\",color=\"#6b1d1d\"];"));
+        assert!(output.contains("<tr><td><font color=\"#6b1d1d\">@Bean</font></td></tr>"));
+
+        let options = RenderOptionsBuilder::default()
+            .palette(Palette::Colorblind)
+            .build()
+            .unwrap();
+        let output = render_to_string(&classes, &options);
+        assert!(output.contains("[label=\"@Bean\",color=\"#e69f00\"];"));
+        // Colors from a palette file replace those of the palette
+        let options = RenderOptionsBuilder::default()
+            .color_overrides(
+                "edge.bean = #123456\nnode.configuration = #abcdef"
+                    .parse()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let output = render_to_string(&classes, &options);
+        assert!(output.contains("FooConfig -> Foo [label=\"@Bean\",color=\"#123456\"];"));
+        assert!(output.contains("<tr><td><font color=\"#123456\">@Bean</font></td></tr>"));
+        assert!(output.contains("FooConfig [fillcolor=\"#abcdef\""));
+    }
+
+    #[test]
+    fn render_excluded_autoconfigs_tooltip() {
+        let class = component("Application", ComponentType::SpringBootApplication, &[])
+            .excluded_autoconfigs(vec![
+                "DataSourceAutoConfiguration".to_string(),
+                "SecurityAutoConfiguration".to_string(),
+            ])
+            .build()
+            .unwrap();
+        let output = render_to_string(&[class], &RenderOptions::default());
+        assert!(output.contains(
+            "tooltip=\"excludes: DataSourceAutoConfiguration, SecurityAutoConfiguration\"];"
+        ));
+    }
+
+    #[test]
+    fn render_rank_entrypoints() {
+        let application = component("Application", ComponentType::SpringBootApplication, &[])
+            .build()
+            .unwrap();
+        let repository = component("FooRepository", ComponentType::Repository, &[])
+            .build()
+            .unwrap();
+        let injecting_repository = component("BarRepository", ComponentType::Repository, &[])
+            .autowires(vec![Autowired::new(
+                "FooRepository".to_string(),
+                "foo".to_string(),
+            )])
+            .build()
+            .unwrap();
+        let classes = [application, repository, injecting_repository];
+        let output = render_to_string(&classes, &RenderOptions::default());
+        assert!(!output.contains("rank="));
+
+        let options = RenderOptionsBuilder::default()
+            .rank_entrypoints(true)
+            .build()
+            .unwrap();
+        let output = render_to_string(&classes, &options);
+        assert!(output.contains("    { rank=source; Application; }\n"));
+        assert!(output.contains("    { rank=sink; FooRepository; }\n"));
+    }
+
+    #[test]
+    fn render_label_styles() {
+        let class = component("FooService", ComponentType::Service, &[])
+            .package("com.example.foo".to_string())
+            .build()
+            .unwrap();
+        let classes = [class];
+
+        let output = render_to_string(&classes, &RenderOptions::default());
+        assert!(output.contains("    FooService [fillcolor=\"#a81347\"style=filled,shape=box];"));
+
+        let options = RenderOptionsBuilder::default()
+            .label_style(LabelStyle::Qualified)
+            .build()
+            .unwrap();
+        let output = render_to_string(&classes, &options);
+        assert!(output.contains(
+            "    FooService [fillcolor=\"#a81347\"style=filled,shape=box,label=\"com.example.foo.FooService\"];"
+        ));
+    }
+
+    #[test]
+    fn render_header_comments() {
+        let options = RenderOptionsBuilder::default()
+            .features("import,autowired,bean".parse().unwrap())
+            .source_path(Some("./src".to_string()))
+            .build()
+            .unwrap();
+        let output = render_to_string(&[service()], &options);
+        let header = format!(
+            "// features: import,autowired,bean\n// path: ./src\n// version: {}\ndigraph Components {{\n",
+            env!("CARGO_PKG_VERSION")
+        );
+        assert!(output.starts_with(&header));
+    }
+
+    #[test]
+    fn render_truncated_edge_labels() {
+        let controller = component("FooController", ComponentType::Controller, &[])
+            .autowires(vec![Autowired::new(
+                "FooRepository".to_string(),
+                "fooRepository".to_string(),
+            )])
+            .build()
+            .unwrap();
+        let options = RenderOptionsBuilder::default()
+            .show_names(true)
+            .edge_label_max_len(Some(14))
+            .build()
+            .unwrap();
+        let output = render_to_string(std::slice::from_ref(&controller), &options);
+        assert!(output.contains(
+            "FooController -> FooRepository [label=\"@Autowired ...\",tooltip=\"@Autowired fooRepository\",color=\"#1f77b4\"];"
+        ));
+
+        let options = RenderOptionsBuilder::default()
+            .edge_label_max_len(Some(14))
+            .build()
+            .unwrap();
+        let output = render_to_string(&[controller], &options);
+        assert!(output
+            .contains("FooController -> FooRepository [label=\"@Autowired\",color=\"#1f77b4\"];"));
+
+        // Labels of every kind of edge are truncated
+        let config = component("FooConfig", ComponentType::Configuration, &[])
+            .imports(vec!["org.lib.LibraryConfig".to_string()])
+            .build()
+            .unwrap();
+        let options = RenderOptionsBuilder::default()
+            .group_external_imports(true)
+            .edge_label_max_len(Some(14))
+            .build()
+            .unwrap();
+        let output = render_to_string(&[config], &options);
+        assert!(output.contains(
+            "FooConfig -> \"org.lib\" [label=\"@Import Lib...\",tooltip=\"@Import LibraryConfig\",color="
+        ));
+    }
+
+    #[test]
+    fn render_lifecycle_indicator() {
+        let class = component("FooService", ComponentType::Service, &[])
+            .post_construct(true)
+            .build()
+            .unwrap();
+        let output = render_to_string(&[class], &RenderOptions::default());
+        assert!(output.contains("label=\"FooService (init)\""));
+    }
+
+    #[test]
+    fn render_comparison_marks_added_and_removed() {
+        let graph = |repository: &str| {
+            Graph::new(&[
+                component(repository, ComponentType::Repository, &[])
+                    .build()
+                    .unwrap(),
+                component("FooService", ComponentType::Service, &[repository])
+                    .build()
+                    .unwrap(),
+            ])
+        };
+        let baseline = graph("FooRepository");
+        let current = graph("BarRepository");
+        let mut buf = Vec::new();
+        render_comparison(&mut buf, &current, &baseline, &RenderOptions::default()).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+
+        assert!(output
+            .contains("FooService [label=\"FooService\",fillcolor=\"#a81347\",style=filled];"));
+        assert!(output.contains("BarRepository [label=\"BarRepository\",fillcolor=\"#e06907\",style=filled,color=green,fontcolor=green,penwidth=2];"));
+        assert!(output.contains(
+            "FooRepository [label=\"FooRepository\",color=red,fontcolor=red,style=dashed];"
+        ));
+        assert!(output.contains("FooService -> BarRepository [label=\"@Autowired\",color=green,fontcolor=green,penwidth=2];"));
+        assert!(output.contains(
+            "FooService -> FooRepository [label=\"@Autowired\",color=red,fontcolor=red,style=dashed];"
+        ));
+    }
+
+    #[test]
+    fn render_merged_parallel_edges() {
+        let controller = component("FooController", ComponentType::Controller, &[])
+            .autowires(vec![Autowired::new(
+                "FooRepository".to_string(),
+                "fooRepository".to_string(),
+            )])
+            .parameters(vec![Parameter {
+                annotations: vec![],
+                class: "FooRepository".to_string(),
+                name: "repository".to_string(),
+                collection: false,
+                qualifier: None,
+            }])
+            .build()
+            .unwrap();
+        let options = RenderOptionsBuilder::default()
+            .merge_parallel_edges(true)
+            .build()
+            .unwrap();
+        let output = render_to_string(std::slice::from_ref(&controller), &options);
+        assert_eq!(1, output.matches("FooController -> FooRepository").count());
+        assert!(output.contains(
+            "FooController -> FooRepository [label=\"Constructor, @Autowired\",color=\"#2ca02c\"];"
+        ));
+
+        let output = render_to_string(&[controller], &RenderOptions::default());
+        assert_eq!(2, output.matches("FooController -> FooRepository").count());
+
+        // Edges of other kinds are merged as well
+        let config = component("FooConfig", ComponentType::Configuration, &[])
+            .imports(vec!["BarConfig".to_string()])
+            .autowires(vec![Autowired::new(
+                "BarConfig".to_string(),
+                "barConfig".to_string(),
+            )])
+            .build()
+            .unwrap();
+        let output = render_to_string(&[config], &options);
+        assert!(output
+            .contains("FooConfig -> BarConfig [label=\"@Import, @Autowired\",color=\"#404040\"];"));
+    }
+}
@@ -0,0 +1,192 @@
+//! Statistics about how many of the matched files could be understood, and how long
+//! it took.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// What to do with files that cannot be parsed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseErrorPolicy {
+    /// Skip the file silently, without failing the exit status.
+    Ignore,
+    /// Skip the file with a warning.
+    #[default]
+    Warn,
+    /// Stop at the first file that cannot be parsed.
+    Fail,
+}
+
+impl Display for ParseErrorPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseErrorPolicy::Ignore => write!(f, "ignore"),
+            ParseErrorPolicy::Warn => write!(f, "warn"),
+            ParseErrorPolicy::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+impl FromStr for ParseErrorPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ignore" => Ok(ParseErrorPolicy::Ignore),
+            "warn" => Ok(ParseErrorPolicy::Warn),
+            "fail" => Ok(ParseErrorPolicy::Fail),
+            _ => Err(format!("unknown parse error policy {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkipReason {
+    Read(String),
+    Parse(String),
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Read(reason) => write!(f, "read error: {}", reason),
+            SkipReason::Parse(reason) => write!(f, "parse error: {}", reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    parsed: usize,
+    skipped: BTreeMap<SkipReason, usize>,
+}
+
+impl ParseStats {
+    pub fn record_parsed(&mut self) {
+        self.parsed += 1;
+    }
+
+    pub fn record_skipped(&mut self, reason: SkipReason) {
+        *self.skipped.entry(reason).or_default() += 1;
+    }
+
+    /// Number of files that matched the filters.
+    pub fn matched(&self) -> usize {
+        self.parsed + self.skipped()
+    }
+
+    pub fn parsed(&self) -> usize {
+        self.parsed
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.skipped.values().sum()
+    }
+
+    /// Number of files that were read but could not be parsed.
+    pub fn parse_errors(&self) -> usize {
+        self.skipped
+            .iter()
+            .filter(|(reason, _)| matches!(reason, SkipReason::Parse(_)))
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+impl Display for ParseStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Matched {} files: {} parsed, {} skipped",
+            self.matched(),
+            self.parsed(),
+            self.skipped()
+        )?;
+        for (reason, count) in &self.skipped {
+            write!(f, "\n  {} {}", count, reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// A phase of the pipeline, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Walk,
+    Read,
+    Parse,
+    Render,
+}
+
+/// Wall-clock time spent in each phase, summed over all files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timings {
+    elapsed: BTreeMap<Phase, Duration>,
+}
+
+impl Timings {
+    /// Runs `f`, adding the time it takes to `phase`.
+    pub fn time<T>(&mut self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(phase, start.elapsed());
+        result
+    }
+
+    pub fn record(&mut self, phase: Phase, elapsed: Duration) {
+        *self.elapsed.entry(phase).or_default() += elapsed;
+    }
+
+    pub fn elapsed(&self, phase: Phase) -> Duration {
+        self.elapsed.get(&phase).copied().unwrap_or_default()
+    }
+}
+
+impl Display for Timings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total: Duration = self.elapsed.values().sum();
+        write!(f, "Took {:.2?}", total)?;
+        for (phase, elapsed) in &self.elapsed {
+            write!(f, "\n  {:?}: {:.2?}", phase, elapsed)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ParseStats, Phase, SkipReason, Timings};
+
+    #[test]
+    fn parse_stats_display() {
+        let mut stats = ParseStats::default();
+        stats.record_parsed();
+        stats.record_parsed();
+        stats.record_skipped(SkipReason::Parse("Fail".to_string()));
+        stats.record_skipped(SkipReason::Read("invalid UTF-8".to_string()));
+        stats.record_skipped(SkipReason::Parse("Fail".to_string()));
+        assert_eq!(5, stats.matched());
+        assert_eq!(
+            "Matched 5 files: 2 parsed, 3 skipped\n  1 read error: invalid UTF-8\n  2 parse error: Fail",
+            stats.to_string()
+        );
+    }
+    #[test]
+    fn timings_display() {
+        let mut timings = Timings::default();
+        timings.record(Phase::Parse, Duration::from_millis(3));
+        timings.record(Phase::Walk, Duration::from_millis(1));
+        timings.record(Phase::Parse, Duration::from_millis(2));
+        assert_eq!(Duration::from_millis(5), timings.elapsed(Phase::Parse));
+        assert_eq!(Duration::ZERO, timings.elapsed(Phase::Render));
+        assert_eq!(
+            "Took 6.00ms\n  Walk: 1.00ms\n  Parse: 5.00ms",
+            timings.to_string()
+        );
+    }
+}
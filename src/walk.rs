@@ -0,0 +1,315 @@
+//! Discovery and reading of Java and Groovy source files.
+
+use derive_builder::Builder;
+use ignore::WalkBuilder;
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+pub fn read_file(path: &Path) -> Result<String, Box<dyn Error>> {
+    // Read file contents
+    let f = File::open(path)?;
+    let mut f = BufReader::new(f);
+    let mut buf = String::new();
+    f.read_to_string(&mut buf)?;
+    Ok(normalize_source(&buf))
+}
+
+/// Strips a leading byte order mark, converts CRLF line endings to LF and blanks out comments.
+pub fn normalize_source(source: &str) -> String {
+    let source = source
+        .strip_prefix('\u{feff}')
+        .unwrap_or(source)
+        .replace("\r\n", "\n");
+    blank_comments(&source)
+}
+
+/// Replaces comments with spaces, keeping line breaks so that positions stay the same.
+/// Commented out code and decompiler notes would otherwise be parsed as code.
+fn blank_comments(source: &str) -> String {
+    enum State {
+        Code,
+        Literal(char),
+        LineComment,
+        BlockComment,
+    }
+    let mut state = State::Code;
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match state {
+            State::Code => match (c, chars.peek()) {
+                ('/', Some('/')) => state = State::LineComment,
+                ('/', Some('*')) => {
+                    chars.next();
+                    out.push(' ');
+                    state = State::BlockComment;
+                }
+                ('"' | '\'', _) => state = State::Literal(c),
+                _ => {}
+            },
+            State::Literal(quote) => match c {
+                '\\' => {
+                    out.push(c);
+                    if let Some(escaped) = chars.next() {
+                        out.push(escaped);
+                    }
+                    continue;
+                }
+                c if c == quote => state = State::Code,
+                _ => {}
+            },
+            State::LineComment if c == '\n' => state = State::Code,
+            State::BlockComment if c == '*' && chars.peek() == Some(&'/') => {
+                chars.next();
+                out.push_str("  ");
+                state = State::Code;
+                continue;
+            }
+            _ => {}
+        }
+        match state {
+            State::LineComment | State::BlockComment if c != '\n' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Builder)]
+pub struct WalkOptions {
+    /// Directory to search from.
+    #[builder(default = "PathBuf::from(\"./\")")]
+    root: PathBuf,
+    /// Substring that paths must contain.
+    #[builder(default)]
+    package: String,
+    /// Substrings that exclude a path when contained in it.
+    #[builder(default)]
+    exclude: Vec<String>,
+    /// Maximum directory depth to descend into, unlimited if unset.
+    #[builder(default)]
+    max_depth: Option<usize>,
+    /// Include files excluded by `.gitignore` and `.ignore` files.
+    #[builder(default)]
+    no_ignore: bool,
+    /// Files to read instead of searching the root, such as the files changed in a build.
+    #[builder(default)]
+    files: Option<Vec<PathBuf>>,
+}
+
+/// Extensions of the source files that are parsed. Groovy is close enough to Java
+/// for the parser to understand simple configuration classes.
+pub const SOURCE_EXTENSIONS: &[&str] = &["java", "groovy"];
+
+/// Java files that never contain a class declaration.
+const NON_CLASS_FILES: &[&str] = &["package-info.java", "module-info.java"];
+
+impl WalkOptions {
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+/// Whether the path has one of the [`SOURCE_EXTENSIONS`].
+pub fn is_source_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+}
+
+/// The source files to parse, either the given files or those found under the root.
+/// The package filter and exclusions apply to both.
+pub fn source_files(options: &WalkOptions) -> Box<dyn Iterator<Item = PathBuf> + '_> {
+    let paths: Box<dyn Iterator<Item = PathBuf>> = match &options.files {
+        Some(files) => Box::new(files.iter().cloned()),
+        None => Box::new(
+            WalkBuilder::new(&options.root)
+                .max_depth(options.max_depth)
+                .git_ignore(!options.no_ignore)
+                .git_global(!options.no_ignore)
+                .git_exclude(!options.no_ignore)
+                .ignore(!options.no_ignore)
+                .build()
+                .filter_map(|e| e.ok())
+                .map(|e| e.into_path()),
+        ),
+    };
+    Box::new(paths.filter(move |path| {
+        // Path must be a file. Listed files that are missing are kept, so that
+        // reading them fails and is reported.
+        if !path.is_file() && (options.files.is_none() || path.exists()) {
+            return false;
+        }
+
+        // Must have a source extension
+        let is_source = is_source_file(path);
+
+        // Package and module descriptors declare no classes
+        let file_name = path.file_name().unwrap_or_default();
+        if NON_CLASS_FILES.iter().any(|f| file_name == *f) {
+            tracing::debug!("Skipping {:?}", file_name);
+            return false;
+        }
+
+        // Path must contain user search
+        let Some(path) = path.to_str() else {
+            tracing::warn!("Path is not valid UTF-8: {:?}", path);
+            return false;
+        };
+        let is_right_package = path.contains(&options.package);
+
+        // Path must not contain any of the exclusions
+        let is_excluded = options.exclude.iter().any(|e| path.contains(e));
+
+        is_source && is_right_package && !is_excluded
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use crate::class::parse_class;
+
+    use super::{normalize_source, read_file, source_files, WalkOptionsBuilder};
+
+    fn write_java(root: &Path, path: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "package a.b.c;\n\npublic class Foo {}\n").unwrap();
+    }
+
+    #[test]
+    fn read_file_normalizes_bom_and_crlf() {
+        let lf = "package a.b.c;\n\n@Service\npublic class Foo {\n    @Autowired\n    private Bar bar;\n}\n";
+        let crlf = format!("\u{feff}{}", lf.replace('\n', "\r\n"));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Foo.java");
+        fs::write(&path, crlf).unwrap();
+
+        let content = read_file(&path).unwrap();
+        assert_eq!(lf, content);
+        assert_eq!(parse_class(lf).unwrap().1, parse_class(&content).unwrap().1);
+    }
+
+    #[test]
+    fn normalize_source_blanks_comments() {
+        let source =
+            "@Service // @Controller\n/* @Bean\n */ String s = \"/* kept */\"; char c = '\\'';\n";
+        let expected =
+            "@Service               \n        \n    String s = \"/* kept */\"; char c = '\\'';\n";
+        assert_eq!(expected, normalize_source(source));
+    }
+
+    #[test]
+    fn source_files_finds_groovy_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        write_java(dir.path(), "a/b/c/Foo.java");
+        write_java(dir.path(), "a/b/c/Bar.groovy");
+        write_java(dir.path(), "a/b/c/Baz.kt");
+
+        let options = WalkOptionsBuilder::default()
+            .root(dir.path().to_path_buf())
+            .build()
+            .unwrap();
+        let mut files: Vec<_> = source_files(&options)
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        assert_eq!(vec!["Bar.groovy", "Foo.java"], files);
+    }
+
+    #[test]
+    fn source_files_skips_excluded_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        write_java(dir.path(), "src/main/java/a/b/c/Foo.java");
+        write_java(dir.path(), "target/generated-sources/a/b/c/Bar.java");
+        write_java(dir.path(), "src/fixtures/a/b/c/Baz.java");
+
+        let options = WalkOptionsBuilder::default()
+            .root(dir.path().to_path_buf())
+            .exclude(vec![
+                "target/generated-sources".to_string(),
+                "fixtures".to_string(),
+            ])
+            .build()
+            .unwrap();
+        let files: Vec<_> = source_files(&options)
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(vec!["Foo.java".to_string()], files);
+    }
+
+    #[test]
+    fn source_files_skips_package_info() {
+        let dir = tempfile::tempdir().unwrap();
+        write_java(dir.path(), "a/b/c/Foo.java");
+        fs::write(
+            dir.path().join("a/b/c/package-info.java"),
+            "@NonNullApi\npackage a.b.c;\n\nimport org.springframework.lang.NonNullApi;\n",
+        )
+        .unwrap();
+
+        let options = WalkOptionsBuilder::default()
+            .root(dir.path().to_path_buf())
+            .build()
+            .unwrap();
+        let files: Vec<_> = source_files(&options)
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(vec!["Foo.java".to_string()], files);
+    }
+
+    #[test]
+    fn source_files_respects_max_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        write_java(dir.path(), "Top.java");
+        write_java(dir.path(), "a/Middle.java");
+        write_java(dir.path(), "a/b/Deep.java");
+
+        let options = WalkOptionsBuilder::default()
+            .root(dir.path().to_path_buf())
+            .max_depth(Some(2))
+            .build()
+            .unwrap();
+        let mut files: Vec<_> = source_files(&options)
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        assert_eq!(
+            vec!["Middle.java".to_string(), "Top.java".to_string()],
+            files
+        );
+    }
+
+    #[test]
+    fn source_files_respects_gitignore_unless_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join(".gitignore"), "generated/\n").unwrap();
+        write_java(dir.path(), "src/Foo.java");
+        write_java(dir.path(), "generated/Bar.java");
+
+        let files = |no_ignore| {
+            let options = WalkOptionsBuilder::default()
+                .root(dir.path().to_path_buf())
+                .no_ignore(no_ignore)
+                .build()
+                .unwrap();
+            let mut files: Vec<_> = source_files(&options)
+                .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            files.sort();
+            files
+        };
+        assert_eq!(vec!["Foo.java".to_string()], files(false));
+        assert_eq!(
+            vec!["Bar.java".to_string(), "Foo.java".to_string()],
+            files(true)
+        );
+    }
+}